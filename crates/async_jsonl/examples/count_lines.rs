@@ -25,7 +25,7 @@ async fn main() -> anyhow::Result<()> {
     let reader = Cursor::new(sample_data.as_bytes());
     let jsonl = Jsonl::new(reader);
 
-    let line_count = JsonlReader::count(jsonl).await;
+    let line_count = JsonlReader::count(jsonl).await?;
     println!("Total lines in sample data: {}", line_count);
 
     // Example 2: Count lines in a file
@@ -48,7 +48,7 @@ async fn main() -> anyhow::Result<()> {
 
     // Count lines in the file
     let jsonl_file = Jsonl::from_path(temp_file_path).await?;
-    let file_line_count = JsonlReader::count(jsonl_file).await;
+    let file_line_count = JsonlReader::count(jsonl_file).await?;
     println!(
         "Total lines in file '{}': {}",
         temp_file_path, file_line_count
@@ -60,14 +60,14 @@ async fn main() -> anyhow::Result<()> {
     // Empty data
     let empty_reader = Cursor::new(b"");
     let empty_jsonl = Jsonl::new(empty_reader);
-    let empty_count = JsonlReader::count(empty_jsonl).await;
+    let empty_count = JsonlReader::count(empty_jsonl).await?;
     println!("Lines in empty data: {}", empty_count);
 
     // Single line
     let single_line = r#"{"single": "record"}"#;
     let single_reader = Cursor::new(single_line.as_bytes());
     let single_jsonl = Jsonl::new(single_reader);
-    let single_count = JsonlReader::count(single_jsonl).await;
+    let single_count = JsonlReader::count(single_jsonl).await?;
     println!("Lines in single-line data: {}", single_count);
 
     // Data with empty lines (they get filtered out)
@@ -81,7 +81,7 @@ async fn main() -> anyhow::Result<()> {
 "#;
     let empty_lines_reader = Cursor::new(data_with_empty_lines.as_bytes());
     let empty_lines_jsonl = Jsonl::new(empty_lines_reader);
-    let filtered_count = JsonlReader::count(empty_lines_jsonl).await;
+    let filtered_count = JsonlReader::count(empty_lines_jsonl).await?;
     println!(
         "Lines in data with empty lines (filtered): {}",
         filtered_count
@@ -106,7 +106,7 @@ async fn main() -> anyhow::Result<()> {
     let large_jsonl = Jsonl::new(large_reader);
 
     let start = std::time::Instant::now();
-    let large_count = JsonlReader::count(large_jsonl).await;
+    let large_count = JsonlReader::count(large_jsonl).await?;
     let elapsed = start.elapsed();
 
     println!("Counted {} lines in {:?}", large_count, elapsed);
@@ -153,7 +153,7 @@ async fn main() -> anyhow::Result<()> {
 
         // Count entries in this log file
         let log_jsonl = Jsonl::from_path(file_path).await?;
-        let log_count = JsonlReader::count(log_jsonl).await;
+        let log_count = JsonlReader::count(log_jsonl).await?;
         total_log_entries += log_count;
 
         println!("Log file '{}': {} entries", file_path, log_count);
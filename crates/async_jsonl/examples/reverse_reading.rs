@@ -3,7 +3,7 @@
 //! This example shows how to use the .get_rev_n() method to read JSONL files
 //! from end to beginning, similar to the Unix `tail` command.
 
-use async_jsonl::{Jsonl, JsonlReader};
+use async_jsonl::{Jsonl, JsonlSeekReader};
 use futures::StreamExt;
 use std::io::Cursor;
 use tokio::fs::File;
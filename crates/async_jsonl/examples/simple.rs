@@ -2,7 +2,6 @@ use async_jsonl::{Jsonl, JsonlDeserialize};
 use futures::StreamExt;
 use serde::Deserialize;
 use std::io::Cursor;
-use tokio;
 
 #[derive(Debug, Deserialize)]
 struct Person {
@@ -0,0 +1,115 @@
+use crate::count::is_json;
+use crate::{JsonlError, JsonlWriter, Location};
+use async_rev_buf::RevBufReader;
+use std::io::SeekFrom;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use tokio::fs::{File, OpenOptions};
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt, BufWriter};
+
+/// Longest last record checked for being valid JSON. Longer ones are taken
+/// for torn without reading them whole, so a large torn tail cannot exhaust
+/// memory.
+const MAX_TAIL_RECORD: usize = 64 * 1024 * 1024;
+
+/// What to do with a torn last record found by
+/// [`JsonlWriter::open_append`].
+///
+/// The last record is torn when the file does not end with a newline, or
+/// when its last record is not valid JSON, as happens when a process dies in
+/// the middle of an append. A last record longer than 64 MiB is not checked
+/// and counts as torn.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum TornTailPolicy {
+    /// Cut the torn record off the file.
+    #[default]
+    Truncate,
+    /// Append the torn record to the file at this path, on a line of its
+    /// own, then cut it off the file.
+    Quarantine(PathBuf),
+    /// Leave the file untouched and fail with [`JsonlError::Truncated`].
+    Refuse,
+}
+
+impl JsonlWriter<BufWriter<File>> {
+    /// Open the file at `path` for appending records, creating it if needed.
+    ///
+    /// The end of the file is read backwards first, and a torn last record
+    /// is handled according to `policy`, so new records never get glued to
+    /// a broken one.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use async_jsonl::{JsonlWriter, TornTailPolicy};
+    /// use serde_json::json;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> anyhow::Result<()> {
+    ///     let policy = TornTailPolicy::Quarantine("events.jsonl.torn".into());
+    ///     let mut writer = JsonlWriter::open_append("events.jsonl", policy).await?;
+    ///
+    ///     writer.write(&json!({"event": "started"})).await?;
+    ///     writer.flush().await?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn open_append<P: AsRef<Path>>(
+        path: P,
+        policy: TornTailPolicy,
+    ) -> Result<Self, JsonlError> {
+        let mut file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(path)
+            .await?;
+
+        if let Some((torn, record)) = find_torn_tail(&mut file).await? {
+            match policy {
+                TornTailPolicy::Truncate => {}
+                TornTailPolicy::Quarantine(path) => {
+                    let mut quarantine = OpenOptions::new()
+                        .append(true)
+                        .create(true)
+                        .open(path)
+                        .await?;
+                    file.seek(SeekFrom::Start(torn.start)).await?;
+                    let mut record = (&mut file).take(torn.end - torn.start);
+                    tokio::io::copy(&mut record, &mut quarantine).await?;
+                    quarantine.write_all(b"\n").await?;
+                    quarantine.sync_all().await?;
+                }
+                TornTailPolicy::Refuse => {
+                    return Err(JsonlError::truncated(
+                        Location::at_offset(torn.start),
+                        &record,
+                    ))
+                }
+            }
+            file.set_len(torn.start).await?;
+            file.sync_all().await?;
+        }
+
+        Ok(JsonlWriter::new(BufWriter::new(file)))
+    }
+}
+
+/// Find where the torn last record of `file` lies, if there is one, along
+/// with at most [`MAX_TAIL_RECORD`] bytes of its start.
+async fn find_torn_tail(file: &mut File) -> Result<Option<(Range<u64>, Vec<u8>)>, JsonlError> {
+    let size = file.metadata().await?.len();
+    let mut reader = RevBufReader::new(file);
+    let mut line = Vec::new();
+
+    while let Some(range) = reader.read_line_raw(&mut line, MAX_TAIL_RECORD).await? {
+        let cut = range.end - range.start > line.len() as u64;
+        // Whitespace after the last record is harmless
+        if !cut && line.iter().all(u8::is_ascii_whitespace) {
+            continue;
+        }
+        let torn = cut || range.end == size || !is_json(&line);
+        return Ok(torn.then_some((range, line)));
+    }
+    Ok(None)
+}
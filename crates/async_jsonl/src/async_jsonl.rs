@@ -1,18 +1,30 @@
+use crate::builder::ReadOptions;
+use crate::line_reader::{LineReader, Source};
+use crate::{CountReport, DeserializeStream, ErrorPolicy, JsonlError, LazyRecord, TaggedStream};
 use futures::Stream;
+use serde::de::DeserializeSeed;
 use serde::Deserialize;
+use serde_json::value::RawValue;
 use serde_json::Value;
-use tokio::io::{BufReader, Lines};
+use std::ops::Range;
 
 /// Iterator to read JSONL file as raw JSON strings
 pub struct Jsonl<R> {
-    pub(crate) lines: Lines<BufReader<R>>,
+    pub(crate) lines: LineReader<Source<R>>,
+    pub(crate) options: ReadOptions,
+    /// Holds the current record when it had to be rewritten, e.g. lossily decoded
+    pub(crate) scratch: String,
+    /// Whether the first record was read as a header, and is no data record
+    pub(crate) has_header: bool,
 }
 
 /// Main trait for reading JSONL (JSON Lines) files with async capabilities.
 ///
 /// This trait provides methods to read and process JSONL files asynchronously.
 /// It combines streaming capabilities with deserialization and line selection methods.
-/// The trait is implemented by `Jsonl<R>` where `R` implements `AsyncRead + AsyncSeek`.
+/// The trait is implemented by `Jsonl<R>` for any `R: AsyncRead`, including
+/// sources that cannot seek such as stdin, sockets or a child process' stdout.
+/// Readers over seekable sources also implement [`JsonlSeekReader`].
 ///
 /// # Examples
 ///
@@ -48,7 +60,7 @@ pub struct Jsonl<R> {
 /// }
 /// ```
 ///
-/// ## Reading last n lines (tail-like functionality)
+/// ## Reading the last n lines of a pipe
 ///
 /// ```ignore
 /// use async_jsonl::{Jsonl, JsonlReader};
@@ -56,10 +68,10 @@ pub struct Jsonl<R> {
 ///
 /// #[tokio::main]
 /// async fn main() -> anyhow::Result<()> {
-///     let reader = Jsonl::from_path("log.jsonl").await?;
+///     let reader = Jsonl::new(tokio::io::stdin());
 ///     
-///     // Get last 10 lines (like tail)
-///     let last_ten = reader.last_n(10).await?;
+///     // Keep only the last 10 lines while reading through the input
+///     let last_ten = reader.last_n_buffered(10).await?;
 ///     
 ///     let lines: Vec<String> = last_ten
 ///         .collect::<Vec<_>>()
@@ -75,11 +87,11 @@ pub struct Jsonl<R> {
 /// }
 /// ```
 #[async_trait::async_trait]
-pub trait JsonlReader: JsonlDeserialize + JsonlValueDeserialize + Stream + Send + Sync {
+pub trait JsonlReader: JsonlDeserialize + JsonlValueDeserialize + Stream + Send {
     /// Stream type for the first n lines
-    type NLines: Stream<Item = anyhow::Result<String>>;
+    type NLines: Stream<Item = Result<String, JsonlError>>;
     /// Stream type for the last n lines (in reverse order)
-    type NLinesRev: Stream<Item = anyhow::Result<String>>;
+    type NLinesRev: Stream<Item = Result<String, JsonlError>>;
 
     /// Get the first `n` lines from the JSONL stream.
     ///
@@ -90,6 +102,8 @@ pub trait JsonlReader: JsonlDeserialize + JsonlValueDeserialize + Stream + Send
     /// # Returns
     ///
     /// Returns a stream of the first `n` lines as `String`s, or an error if reading fails.
+    /// Lines that fail to read, such as overlong lines or invalid UTF-8,
+    /// count towards `n` and are yielded as errors.
     ///
     /// # Examples
     ///
@@ -122,10 +136,116 @@ pub trait JsonlReader: JsonlDeserialize + JsonlValueDeserialize + Stream + Send
     ///     Ok(())
     /// }
     /// ```
-    async fn first_n(self, n: usize) -> anyhow::Result<Self::NLines>;
+    async fn first_n(self, n: usize) -> Result<Self::NLines, JsonlError>;
 
+    /// Skip the next `n` lines, returning the reader positioned after them.
+    ///
+    /// Skipped lines are found by scanning the read buffer for newlines,
+    /// without copying, decoding or parsing them. Every line counts, blank and
+    /// comment lines included, so `skip_n(k)` on a new reader resumes right
+    /// after the line reported as number `k` in a [`Location`](crate::Location).
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use async_jsonl::{Jsonl, JsonlDeserialize, JsonlReader};
+    /// use futures::StreamExt;
+    /// use serde_json::Value;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> anyhow::Result<()> {
+    ///     // Resume after the last line handled by a previous run
+    ///     let reader = Jsonl::from_path("events.jsonl").await?.skip_n(250_000).await?;
+    ///     let mut values = reader.deserialize::<Value>();
+    ///
+    ///     while let Some(value) = values.next().await {
+    ///         println!("{}", value?);
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    async fn skip_n(self, n: usize) -> Result<Self, JsonlError>
+    where
+        Self: Sized;
+
+    /// Read only the lines with 0-based indices in `range`, counted from the
+    /// current position.
+    ///
+    /// Lines before the range are skipped like with [`JsonlReader::skip_n`]
+    /// and the stream ends after the last line of the range, so
+    /// `range(1_000_000..1_000_100)` reads only the first million lines'
+    /// newlines plus a hundred lines.
+    async fn range(self, range: Range<usize>) -> Result<Self, JsonlError>
+    where
+        Self: Sized;
+
+    /// Read only the line with 0-based index `n`, counted from the current
+    /// position.
+    ///
+    /// The returned stream yields at most one record: none when the line is
+    /// past the end of the input or is skipped as a blank or comment line.
+    async fn nth(self, n: usize) -> Result<Self, JsonlError>
+    where
+        Self: Sized;
+
+    /// Get the last `n` lines by reading through the whole input.
+    ///
+    /// Only the last `n` lines are kept while reading, so memory stays bounded
+    /// by `n` lines whatever the size of the input. Lines are returned in
+    /// reverse order, like [`JsonlSeekReader::last_n`], which should be
+    /// preferred when the source can seek.
+    ///
+    /// Lines that fail to read count towards `n` and are returned as errors;
+    /// IO errors abort the read.
+    async fn last_n_buffered(self, n: usize) -> Result<Self::NLinesRev, JsonlError>;
+
+    /// Count the records in the JSONL stream.
+    ///
+    /// Lines are counted by scanning the raw read buffer for newlines, without
+    /// copying or validating them. Blank lines are left out unless the reader
+    /// is configured to yield them, and so are lines starting with a comment
+    /// prefix. When [`Utf8Policy::Skip`](crate::Utf8Policy::Skip) drops lines,
+    /// each line is decoded instead, so the count matches what the stream
+    /// yields. Use [`JsonlReader::count_detailed`] to tell valid records from
+    /// invalid ones.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first IO error of the underlying reader.
+    async fn count(self) -> Result<usize, JsonlError>;
+
+    /// Count the lines of the JSONL stream by kind: blank, comment, valid or
+    /// invalid JSON.
+    ///
+    /// Records are checked for valid JSON without building values or
+    /// allocating per line.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use async_jsonl::{Jsonl, JsonlReader};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> anyhow::Result<()> {
+    ///     let report = Jsonl::from_path("events.jsonl").await?.count_detailed().await?;
+    ///     println!("{} valid, {} invalid of {} lines", report.valid, report.invalid, report.total);
+    ///     Ok(())
+    /// }
+    /// ```
+    async fn count_detailed(self) -> Result<CountReport, JsonlError>;
+}
+
+/// Reading methods that need to seek in the source.
+///
+/// Implemented by `Jsonl<R>` where `R` implements `AsyncRead + AsyncSeek`.
+#[async_trait::async_trait]
+pub trait JsonlSeekReader: JsonlReader {
     /// Get the last `n` lines from the JSONL stream.
     ///
+    /// The source is read backwards from its end, so only the requested lines
+    /// are read. To walk back without knowing `n` up front, use
+    /// [`Jsonl::rev`].
+    ///
     /// # Arguments
     ///
     /// * `n` - The number of lines to retrieve from the end
@@ -138,7 +258,7 @@ pub trait JsonlReader: JsonlDeserialize + JsonlValueDeserialize + Stream + Send
     /// # Examples
     ///
     /// ```ignore
-    /// use async_jsonl::{Jsonl, JsonlReader};
+    /// use async_jsonl::{Jsonl, JsonlSeekReader};
     /// use futures::StreamExt;
     ///
     /// #[tokio::main]
@@ -158,10 +278,7 @@ pub trait JsonlReader: JsonlDeserialize + JsonlValueDeserialize + Stream + Send
     ///     Ok(())
     /// }
     /// ```
-    async fn last_n(self, n: usize) -> anyhow::Result<Self::NLinesRev>;
-
-    /// Count the total number of lines in the JSONL stream.
-    async fn count(self) -> usize;
+    async fn last_n(self, n: usize) -> Result<Self::NLinesRev, JsonlError>;
 }
 
 /// Extension trait to add deserialization capabilities to JSONL readers.
@@ -248,11 +365,189 @@ pub trait JsonlReader: JsonlDeserialize + JsonlValueDeserialize + Stream + Send
 ///     Ok(())
 /// }
 /// ```
+///
+/// ## Choosing What Happens to Bad Lines
+///
+/// ```ignore
+/// use async_jsonl::{ErrorPolicy, Jsonl, JsonlDeserialize};
+/// use futures::StreamExt;
+/// use serde_json::Value;
+///
+/// #[tokio::main]
+/// async fn main() -> anyhow::Result<()> {
+///     let (dead_letters, mut rejected) = futures::channel::mpsc::unbounded();
+///     let reader = Jsonl::from_path("events.jsonl").await?;
+///
+///     let mut values = reader.deserialize_with::<Value>(ErrorPolicy::dead_letter(dead_letters));
+///     while let Some(value) = values.next().await {
+///         println!("{}", value?);
+///     }
+///     println!("{} bad lines", values.skipped());
+///
+///     while let Some(letter) = rejected.next().await {
+///         eprintln!("{:?}: {}", letter.line, letter.error);
+///     }
+///     Ok(())
+/// }
+/// ```
 pub trait JsonlDeserialize {
     /// Deserialize JSON lines into the specified type
-    fn deserialize<T>(self) -> impl Stream<Item = anyhow::Result<T>>
+    fn deserialize<T>(self) -> impl Stream<Item = Result<T, JsonlError>>
     where
         T: for<'a> Deserialize<'a>;
+
+    /// Deserialize JSON lines into the specified type, handling lines that
+    /// fail according to `policy`.
+    fn deserialize_with<T>(self, policy: ErrorPolicy) -> DeserializeStream<Self, T>
+    where
+        T: for<'a> Deserialize<'a>,
+        Self: Sized;
+
+    /// Deserialize JSON lines with a copy of `seed`, so parsing can use
+    /// shared context such as a string interner or a schema registry.
+    ///
+    /// The seed is cloned for every line; keep it cheap to clone, e.g. a
+    /// reference or an `Rc` to the shared state.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use async_jsonl::{Jsonl, JsonlDeserialize};
+    /// use futures::StreamExt;
+    /// use serde::de::{DeserializeSeed, Deserializer, MapAccess, Visitor};
+    /// use std::cell::RefCell;
+    /// use std::collections::HashSet;
+    /// use std::fmt;
+    /// use std::rc::Rc;
+    ///
+    /// /// Reads a record of string fields, sharing one allocation per distinct string
+    /// #[derive(Clone, Copy)]
+    /// struct Interned<'a>(&'a RefCell<HashSet<Rc<str>>>);
+    ///
+    /// impl<'de> DeserializeSeed<'de> for Interned<'_> {
+    ///     type Value = Vec<(Rc<str>, Rc<str>)>;
+    ///
+    ///     fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+    ///         deserializer.deserialize_map(self)
+    ///     }
+    /// }
+    ///
+    /// impl<'de> Visitor<'de> for Interned<'_> {
+    ///     type Value = Vec<(Rc<str>, Rc<str>)>;
+    ///
+    ///     fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    ///         f.write_str("an object of strings")
+    ///     }
+    ///
+    ///     fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+    ///         let mut strings = self.0.borrow_mut();
+    ///         let mut intern = |s: &str| match strings.get(s) {
+    ///             Some(interned) => interned.clone(),
+    ///             None => {
+    ///                 let interned: Rc<str> = s.into();
+    ///                 strings.insert(interned.clone());
+    ///                 interned
+    ///             }
+    ///         };
+    ///         let mut fields = Vec::new();
+    ///         while let Some((key, value)) = map.next_entry::<&str, &str>()? {
+    ///             fields.push((intern(key), intern(value)));
+    ///         }
+    ///         Ok(fields)
+    ///     }
+    /// }
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> anyhow::Result<()> {
+    ///     let strings = RefCell::new(HashSet::new());
+    ///     let mut records = Jsonl::from_path("telemetry.jsonl")
+    ///         .await?
+    ///         .deserialize_seeded(Interned(&strings));
+    ///
+    ///     while let Some(record) = records.next().await {
+    ///         println!("{:?}", record?);
+    ///     }
+    ///     println!("{} distinct strings", strings.borrow().len());
+    ///     Ok(())
+    /// }
+    /// ```
+    fn deserialize_seeded<D, T>(self, seed: D) -> impl Stream<Item = Result<T, JsonlError>>
+    where
+        D: for<'a> DeserializeSeed<'a, Value = T> + Clone;
+
+    /// Deserialize JSON lines with a fresh seed from `factory` for every
+    /// line, for seeds that can't be cloned.
+    fn deserialize_seeded_by<F, D, T>(
+        self,
+        factory: F,
+    ) -> impl Stream<Item = Result<T, JsonlError>>
+    where
+        F: FnMut() -> D,
+        D: for<'a> DeserializeSeed<'a, Value = T>;
+
+    /// Deserialize records of several types, choosing each record's type by
+    /// the string under its top-level `tag` key.
+    ///
+    /// Types are registered per tag value on the returned [`TaggedStream`],
+    /// either converted into a common `E` or passed to async handlers.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use async_jsonl::{Jsonl, JsonlDeserialize};
+    /// use futures::StreamExt;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct User {
+    ///     name: String,
+    /// }
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Event {
+    ///     target: String,
+    /// }
+    ///
+    /// enum Record {
+    ///     User(User),
+    ///     Event(Event),
+    ///     Other(String),
+    /// }
+    ///
+    /// impl From<User> for Record {
+    ///     fn from(user: User) -> Self {
+    ///         Record::User(user)
+    ///     }
+    /// }
+    ///
+    /// impl From<Event> for Record {
+    ///     fn from(event: Event) -> Self {
+    ///         Record::Event(event)
+    ///     }
+    /// }
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> anyhow::Result<()> {
+    ///     let mut records = Jsonl::from_path("mixed.jsonl")
+    ///         .await?
+    ///         .deserialize_tagged::<Record>("type")
+    ///         .on::<User>("user")
+    ///         .on::<Event>("event")
+    ///         .fallback(|record| Ok(Record::Other(record.get().to_string())));
+    ///
+    ///     while let Some(record) = records.next().await {
+    ///         match record? {
+    ///             Record::User(user) => println!("user {}", user.name),
+    ///             Record::Event(event) => println!("event on {}", event.target),
+    ///             Record::Other(raw) => println!("other: {}", raw),
+    ///         }
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    fn deserialize_tagged<E>(self, tag: &str) -> TaggedStream<Self, E>
+    where
+        Self: Sized;
 }
 
 /// Extension trait specifically for deserializing JSONL to `serde_json::Value` objects.
@@ -297,6 +592,10 @@ pub trait JsonlDeserialize {
 ///
 /// ## Processing Mixed JSON Structures
 ///
+/// When records carry a tag naming their type,
+/// [`JsonlDeserialize::deserialize_tagged`] deserializes each one straight
+/// into its type without building `Value`s first.
+///
 /// ```ignore
 /// use async_jsonl::{Jsonl, JsonlValueDeserialize};
 /// use futures::StreamExt;
@@ -373,9 +672,9 @@ pub trait JsonlValueDeserialize {
     ///
     /// # Returns
     ///
-    /// Returns a `Stream` of `anyhow::Result<Value>` where:
+    /// Returns a `Stream` of `Result<Value, JsonlError>` where:
     /// - `Ok(Value)` represents a successfully parsed JSON value
-    /// - `Err(anyhow::Error)` represents parsing errors for invalid JSON lines
+    /// - `Err(JsonlError)` represents parsing errors for invalid JSON lines
     ///
     /// # Examples
     ///
@@ -405,5 +704,14 @@ pub trait JsonlValueDeserialize {
     ///     Ok(())
     /// }
     /// ```
-    fn deserialize_values(self) -> impl Stream<Item = anyhow::Result<Value>>;
+    fn deserialize_values(self) -> impl Stream<Item = Result<Value, JsonlError>>;
+
+    /// Validate JSON lines without building values, yielding each record's raw JSON.
+    ///
+    /// Useful to forward records untouched, or to deserialize them later.
+    fn deserialize_raw(self) -> impl Stream<Item = Result<Box<RawValue>, JsonlError>>;
+
+    /// Validate JSON lines without building values, yielding [`LazyRecord`]s
+    /// whose fields are parsed only when read.
+    fn deserialize_lazy(self) -> impl Stream<Item = Result<LazyRecord, JsonlError>>;
 }
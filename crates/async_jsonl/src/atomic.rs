@@ -0,0 +1,187 @@
+use crate::{Framing, JsonlError, JsonlWriter};
+use futures::{Sink, Stream};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::task::{Context, Poll};
+use tokio::fs::{File, OpenOptions};
+use tokio::io::BufWriter;
+
+/// Distinguishes temporary files created by one process
+static NEXT_TEMP: AtomicUsize = AtomicUsize::new(0);
+
+/// Replaces a JSONL file as a whole, so readers see either the old file or
+/// the new one, never a partly written one.
+///
+/// Records go to a temporary file next to the target, which
+/// [`commit`](AtomicJsonlFile::commit) syncs to disk and renames over the
+/// target. If the writer is dropped without committing, for instance after
+/// an error, the temporary file is deleted and the target is left as it was.
+///
+/// # Examples
+///
+/// ```ignore
+/// use async_jsonl::{AtomicJsonlFile, Jsonl, JsonlDeserialize};
+/// use futures::StreamExt;
+/// use serde_json::Value;
+///
+/// #[tokio::main]
+/// async fn main() -> anyhow::Result<()> {
+///     let mut records = Jsonl::from_path("events.jsonl").await?.deserialize::<Value>();
+///     let mut compacted = AtomicJsonlFile::create("events.jsonl").await?;
+///
+///     while let Some(record) = records.next().await {
+///         let record = record?;
+///         if record["level"] != "debug" {
+///             compacted.write(&record).await?;
+///         }
+///     }
+///     compacted.commit().await?;
+///     Ok(())
+/// }
+/// ```
+pub struct AtomicJsonlFile {
+    // Declared first so the file is closed before the guard deletes it
+    writer: JsonlWriter<BufWriter<File>>,
+    temp: TempPath,
+    target: PathBuf,
+}
+
+impl AtomicJsonlFile {
+    /// Start replacing the file at `path`, which need not exist yet.
+    ///
+    /// The temporary file takes the permissions of the current file, if any.
+    pub async fn create<P: AsRef<Path>>(path: P) -> Result<Self, JsonlError> {
+        let target = path.as_ref().to_path_buf();
+        let name = target.file_name().ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("{} does not name a file", target.display()),
+            )
+        })?;
+        let temp = target.with_file_name(format!(
+            ".{}.{}-{}.tmp",
+            name.to_string_lossy(),
+            std::process::id(),
+            NEXT_TEMP.fetch_add(1, Ordering::Relaxed)
+        ));
+
+        let file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&temp)
+            .await?;
+        let temp = TempPath(Some(temp));
+        if let Ok(metadata) = tokio::fs::metadata(&target).await {
+            file.set_permissions(metadata.permissions()).await?;
+        }
+
+        Ok(Self {
+            writer: JsonlWriter::new(BufWriter::new(file)),
+            temp,
+            target,
+        })
+    }
+
+    /// Set how records are delimited, one per line by default.
+    pub fn framing(mut self, framing: Framing) -> Self {
+        self.writer = self.writer.framing(framing);
+        self
+    }
+
+    /// Serialize `record` and write it, followed by its terminator.
+    pub async fn write<T: Serialize + ?Sized>(&mut self, record: &T) -> Result<(), JsonlError> {
+        self.writer.write(record).await
+    }
+
+    /// Write every record of `records`, returning how many were written.
+    ///
+    /// Stops at the first error of `records` and returns it.
+    pub async fn write_all<S, T>(&mut self, records: S) -> Result<usize, JsonlError>
+    where
+        S: Stream<Item = Result<T, JsonlError>>,
+        T: Serialize,
+    {
+        self.writer.write_all(records).await
+    }
+
+    /// Sync the new contents to disk and move them over the target.
+    ///
+    /// The directory is synced as well, so the new file survives a crash
+    /// once this returns.
+    pub async fn commit(self) -> Result<(), JsonlError> {
+        let Self {
+            writer,
+            mut temp,
+            target,
+        } = self;
+        let file = writer.finish().await?.into_inner();
+        file.sync_all().await?;
+
+        tokio::fs::rename(temp.path(), &target).await?;
+        temp.keep();
+        sync_parent(&target).await?;
+        Ok(())
+    }
+}
+
+impl<T: Serialize> Sink<T> for AtomicJsonlFile {
+    type Error = JsonlError;
+
+    fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), JsonlError>> {
+        Sink::<T>::poll_ready(Pin::new(&mut self.writer), cx)
+    }
+
+    fn start_send(mut self: Pin<&mut Self>, record: T) -> Result<(), JsonlError> {
+        Pin::new(&mut self.writer).start_send(record)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), JsonlError>> {
+        Sink::<T>::poll_flush(Pin::new(&mut self.writer), cx)
+    }
+
+    /// Flushes the records written so far; the file still has to be
+    /// committed.
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), JsonlError>> {
+        Sink::<T>::poll_flush(Pin::new(&mut self.writer), cx)
+    }
+}
+
+/// Deletes the temporary file when dropped, unless it was kept.
+struct TempPath(Option<PathBuf>);
+
+impl TempPath {
+    fn path(&self) -> &Path {
+        self.0.as_deref().expect("temporary file already kept")
+    }
+
+    fn keep(&mut self) {
+        self.0 = None;
+    }
+}
+
+impl Drop for TempPath {
+    fn drop(&mut self) {
+        if let Some(path) = self.0.take() {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+/// Make a rename into the directory of `path` durable.
+#[cfg(unix)]
+async fn sync_parent(path: &Path) -> std::io::Result<()> {
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    File::open(dir).await?.sync_all().await
+}
+
+/// Directories can't be opened for syncing here; renames are durable once
+/// the file system commits them.
+#[cfg(not(unix))]
+async fn sync_parent(_: &Path) -> std::io::Result<()> {
+    Ok(())
+}
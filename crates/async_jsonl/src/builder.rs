@@ -0,0 +1,417 @@
+use crate::line_reader::truncate_line;
+use crate::take_n::{TakeNLines, TakeNLinesReverse};
+use crate::{JsonStream, Jsonl, JsonlError, JsonlLines, JsonlStreamExt, Location};
+use serde::Serialize;
+use std::borrow::Cow;
+use tokio::fs::File;
+use tokio::io::{AsyncRead, AsyncSeek};
+
+/// Default buffer size: 8 KB
+const DEFAULT_BUF_SIZE: usize = 8 * 1024;
+
+/// How whitespace around each line is handled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TrimPolicy {
+    /// Keep the line exactly as read, minus its line terminator.
+    None,
+    /// Remove trailing whitespace only.
+    End,
+    /// Remove leading and trailing whitespace.
+    #[default]
+    Full,
+}
+
+/// What to do with lines that are empty or contain only whitespace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BlankLinePolicy {
+    /// Drop blank lines silently.
+    #[default]
+    Skip,
+    /// Yield blank lines like any other line.
+    Yield,
+    /// Yield a [`JsonlError::BlankLine`] for every blank line.
+    Error,
+}
+
+/// What to do with lines longer than the configured maximum length.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LongLinePolicy {
+    /// Yield a [`JsonlError::LineTooLong`] and continue at the next line.
+    #[default]
+    Error,
+    /// Yield the first `max_line_length` bytes of the line.
+    Truncate,
+}
+
+/// What to do with lines that are not valid UTF-8.
+///
+/// The policy applies to every reader, forward or reverse, so the same file
+/// behaves the same way whichever direction it is read in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Utf8Policy {
+    /// Yield a [`JsonlError::InvalidUtf8`] and continue at the next line.
+    #[default]
+    Error,
+    /// Replace invalid sequences with `U+FFFD`.
+    Lossy,
+    /// Drop the line silently.
+    Skip,
+}
+
+/// Character encoding of the input.
+///
+/// Whatever the encoding, a byte order mark at the start of the input is
+/// dropped rather than glued onto the first record. UTF-16 input is
+/// transcoded to UTF-8 before lines are split and checked, so every other
+/// setting applies unchanged. Byte offsets of UTF-16 input count bytes of the
+/// UTF-8 text in forward readers, and bytes of the source in reverse readers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Encoding {
+    /// UTF-8, with or without a byte order mark.
+    #[default]
+    Utf8,
+    /// UTF-16 little-endian, with or without a byte order mark.
+    Utf16Le,
+    /// UTF-16 big-endian, with or without a byte order mark.
+    Utf16Be,
+    /// UTF-16 when the input starts with a UTF-16 byte order mark, in the
+    /// order it gives, and UTF-8 otherwise.
+    Auto,
+}
+
+/// ASCII record separator, which starts every text of a JSON text sequence.
+pub(crate) const RECORD_SEPARATOR: u8 = 0x1E;
+
+/// How records are delimited in the input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Framing {
+    /// One record per line, ended by `\n` or `\r\n`.
+    #[default]
+    Lines,
+    /// An RFC 7464 JSON text sequence (`application/json-seq`): every record
+    /// starts with an ASCII record separator (`0x1E`) and ends with `\n`.
+    ///
+    /// Records may span lines, and empty records between separators are
+    /// ignored. Line numbers in errors and positions count records. A record
+    /// that does not end in whitespace was cut short, as the RFC requires
+    /// for numbers and literals, and is reported as
+    /// [`JsonlError::Truncated`] instead of being parsed.
+    JsonSeq,
+}
+
+impl Framing {
+    /// Append `value` to `buf` as one record in this framing.
+    ///
+    /// The record never spans lines: serde_json escapes line breaks in
+    /// strings, and those in embedded raw values, which can only be
+    /// whitespace between tokens, are replaced by spaces.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use async_jsonl::Framing;
+    /// use serde_json::json;
+    ///
+    /// let mut buf = Vec::new();
+    /// Framing::JsonSeq.encode(&mut buf, &json!({"id": 1}))?;
+    /// assert_eq!(buf, b"\x1e{\"id\":1}\n");
+    /// ```
+    pub fn encode<T: Serialize + ?Sized>(
+        self,
+        buf: &mut Vec<u8>,
+        value: &T,
+    ) -> Result<(), serde_json::Error> {
+        if self == Framing::JsonSeq {
+            buf.push(RECORD_SEPARATOR);
+        }
+        let start = buf.len();
+        serde_json::to_writer(&mut *buf, value)?;
+        for byte in &mut buf[start..] {
+            if matches!(byte, b'\n' | b'\r') {
+                *byte = b' ';
+            }
+        }
+        buf.push(b'\n');
+        Ok(())
+    }
+}
+
+/// Settings shared by every reader created from a [`JsonlBuilder`].
+#[derive(Debug, Clone)]
+pub(crate) struct ReadOptions {
+    pub(crate) buffer_capacity: usize,
+    pub(crate) trim: TrimPolicy,
+    pub(crate) blank_lines: BlankLinePolicy,
+    pub(crate) comment_prefixes: Vec<String>,
+    pub(crate) max_line_length: Option<usize>,
+    pub(crate) long_lines: LongLinePolicy,
+    pub(crate) utf8: Utf8Policy,
+    pub(crate) encoding: Encoding,
+    pub(crate) framing: Framing,
+}
+
+impl Default for ReadOptions {
+    fn default() -> Self {
+        Self {
+            buffer_capacity: DEFAULT_BUF_SIZE,
+            trim: TrimPolicy::default(),
+            blank_lines: BlankLinePolicy::default(),
+            comment_prefixes: Vec::new(),
+            max_line_length: None,
+            long_lines: LongLinePolicy::default(),
+            utf8: Utf8Policy::default(),
+            encoding: Encoding::default(),
+            framing: Framing::default(),
+        }
+    }
+}
+
+/// Outcome of applying [`ReadOptions`] to a raw line.
+pub(crate) enum LineAction<'a> {
+    /// Hand out this part of the line as a record.
+    Borrowed(&'a str),
+    /// Hand out a rewritten copy of the line as a record.
+    Owned(String),
+    /// Drop the line.
+    Skip,
+    /// Report the line as an error.
+    Reject(JsonlError),
+}
+
+impl ReadOptions {
+    /// Apply the length limit, UTF-8 policy, trimming, comment and blank line
+    /// handling to a line without its terminator.
+    ///
+    /// `overflowed` tells whether bytes of the line were already dropped for
+    /// exceeding the length limit.
+    pub(crate) fn process<'a>(
+        &self,
+        mut line: &'a [u8],
+        location: Location,
+        overflowed: bool,
+    ) -> LineAction<'a> {
+        if let Some(limit) = self.max_line_length {
+            if overflowed || line.len() > limit {
+                match self.long_lines {
+                    LongLinePolicy::Error => {
+                        return LineAction::Reject(JsonlError::line_too_long(location, limit, line))
+                    }
+                    LongLinePolicy::Truncate => line = truncate_line(line, limit),
+                }
+            }
+        }
+
+        let text = match std::str::from_utf8(line) {
+            Ok(text) => Cow::Borrowed(text),
+            Err(e) => match self.utf8 {
+                Utf8Policy::Error => {
+                    return LineAction::Reject(JsonlError::invalid_utf8(location, line, e))
+                }
+                Utf8Policy::Lossy => String::from_utf8_lossy(line),
+                Utf8Policy::Skip => return LineAction::Skip,
+            },
+        };
+
+        match text {
+            Cow::Borrowed(text) => match self.classify(text, location) {
+                Ok(Some(text)) => LineAction::Borrowed(text),
+                Ok(None) => LineAction::Skip,
+                Err(e) => LineAction::Reject(e),
+            },
+            Cow::Owned(text) => match self.classify(&text, location) {
+                Ok(Some(text)) => LineAction::Owned(text.to_string()),
+                Ok(None) => LineAction::Skip,
+                Err(e) => LineAction::Reject(e),
+            },
+        }
+    }
+
+    /// Apply trimming, comment and blank line handling to a decoded line.
+    fn classify<'a>(
+        &self,
+        line: &'a str,
+        location: Location,
+    ) -> Result<Option<&'a str>, JsonlError> {
+        if line.trim().is_empty() {
+            return match self.blank_lines {
+                BlankLinePolicy::Skip => Ok(None),
+                BlankLinePolicy::Yield => Ok(Some(self.apply_trim(line))),
+                BlankLinePolicy::Error => Err(JsonlError::BlankLine { location }),
+            };
+        }
+
+        let start = line.trim_start();
+        if self
+            .comment_prefixes
+            .iter()
+            .any(|prefix| start.starts_with(prefix.as_str()))
+        {
+            return Ok(None);
+        }
+
+        Ok(Some(self.apply_trim(line)))
+    }
+
+    pub(crate) fn apply_trim<'a>(&self, line: &'a str) -> &'a str {
+        match self.trim {
+            TrimPolicy::None => line,
+            TrimPolicy::End => line.trim_end(),
+            TrimPolicy::Full => line.trim(),
+        }
+    }
+}
+
+/// Builder for JSONL readers that don't follow the default conventions.
+///
+/// By default readers use an 8 KB buffer, trim whitespace around every line,
+/// skip blank lines, treat no line as a comment, read UTF-8, reject invalid
+/// UTF-8 and accept lines of any length.
+/// Every reader created from the same builder shares its settings, including
+/// the streams returned by `first_n` and `last_n`.
+///
+/// # Examples
+///
+/// ```ignore
+/// use async_jsonl::{BlankLinePolicy, JsonlBuilder, JsonlDeserialize, TrimPolicy};
+/// use futures::StreamExt;
+/// use serde_json::Value;
+///
+/// #[tokio::main]
+/// async fn main() -> anyhow::Result<()> {
+///     let builder = JsonlBuilder::new()
+///         .buffer_capacity(64 * 1024)
+///         .trim(TrimPolicy::End)
+///         .blank_lines(BlankLinePolicy::Error)
+///         .comment_prefix("#")
+///         .comment_prefix("//")
+///         .max_line_length(1024 * 1024);
+///
+///     let mut values = builder.open("data.jsonl").await?.deserialize::<Value>();
+///     while let Some(value) = values.next().await {
+///         println!("{}", value?);
+///     }
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct JsonlBuilder {
+    options: ReadOptions,
+}
+
+impl JsonlBuilder {
+    /// Create a builder with the default settings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the size of the read buffer, in bytes.
+    pub fn buffer_capacity(mut self, capacity: usize) -> Self {
+        self.options.buffer_capacity = capacity.max(1);
+        self
+    }
+
+    /// Set how whitespace around each line is handled.
+    pub fn trim(mut self, trim: TrimPolicy) -> Self {
+        self.options.trim = trim;
+        self
+    }
+
+    /// Set what happens to blank lines.
+    pub fn blank_lines(mut self, policy: BlankLinePolicy) -> Self {
+        self.options.blank_lines = policy;
+        self
+    }
+
+    /// Skip lines starting with `prefix`, ignoring leading whitespace.
+    ///
+    /// Can be called several times to register more than one prefix.
+    pub fn comment_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.options.comment_prefixes.push(prefix.into());
+        self
+    }
+
+    /// Limit lines to `limit` bytes, excluding the line terminator.
+    ///
+    /// Readers never buffer much more than `limit` bytes of a single line, so a
+    /// corrupt input without newlines cannot exhaust memory. Longer lines are
+    /// handled according to [`JsonlBuilder::long_lines`].
+    pub fn max_line_length(mut self, limit: usize) -> Self {
+        self.options.max_line_length = Some(limit);
+        self
+    }
+
+    /// Set what happens to lines that are not valid UTF-8.
+    pub fn utf8(mut self, policy: Utf8Policy) -> Self {
+        self.options.utf8 = policy;
+        self
+    }
+
+    /// Set the character encoding of the input, UTF-8 by default.
+    pub fn encoding(mut self, encoding: Encoding) -> Self {
+        self.options.encoding = encoding;
+        self
+    }
+
+    /// Set how records are delimited, one per line by default.
+    ///
+    /// Applies to forward and reverse readers alike, though reverse readers
+    /// only split JSON text sequences in UTF-8. Streams of lines passed to
+    /// [`JsonlBuilder::lines`] are already split, so framing doesn't apply
+    /// to them.
+    pub fn framing(mut self, framing: Framing) -> Self {
+        self.options.framing = framing;
+        self
+    }
+
+    /// Set what happens to lines longer than [`JsonlBuilder::max_line_length`].
+    pub fn long_lines(mut self, policy: LongLinePolicy) -> Self {
+        self.options.long_lines = policy;
+        self
+    }
+
+    /// Create a reader over `reader` with these settings.
+    pub fn build<R: AsyncRead + Unpin>(&self, reader: R) -> Jsonl<R> {
+        Jsonl::with_options(reader, self.options.clone())
+    }
+
+    /// Open the file at `path` with these settings.
+    pub async fn open<P: AsRef<std::path::Path>>(
+        &self,
+        path: P,
+    ) -> Result<Jsonl<File>, JsonlError> {
+        let file = File::open(path).await?;
+        Ok(self.build(file))
+    }
+
+    /// Create a reader of back-to-back JSON values over `reader` with these
+    /// settings.
+    ///
+    /// The buffer capacity, encoding and UTF-8 policy apply as to lines, and
+    /// the maximum line length limits the size of each value, handled
+    /// according to [`JsonlBuilder::long_lines`]. Values are not lines, so
+    /// trimming, blank lines, comments and framing don't apply.
+    pub fn json_stream<R: AsyncRead + Unpin>(&self, reader: R) -> JsonStream<R> {
+        JsonStream::with_options(reader, self.options.clone())
+    }
+
+    /// Treat the items of `lines` as JSONL lines with these settings.
+    ///
+    /// Every item is taken as a whole line, so the buffer capacity does not apply.
+    pub fn lines<S: JsonlStreamExt>(&self, lines: S) -> JsonlLines<S> {
+        JsonlLines::new(lines, self.options.clone())
+    }
+
+    /// Read the first `n` lines of `reader` with these settings.
+    pub fn first_n<R: AsyncRead + Unpin>(&self, reader: R, n: usize) -> TakeNLines<R> {
+        self.build(reader).get_n(n)
+    }
+
+    /// Read the last `n` lines of `reader` with these settings, last line first.
+    pub async fn last_n<R: AsyncRead + AsyncSeek + Unpin>(
+        &self,
+        reader: R,
+        n: usize,
+    ) -> Result<TakeNLinesReverse, JsonlError> {
+        self.build(reader).get_rev_n(n).await
+    }
+}
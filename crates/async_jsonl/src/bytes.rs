@@ -0,0 +1,68 @@
+use crate::builder::LineAction;
+use crate::{Jsonl, JsonlError};
+use bytes::Bytes;
+use futures::Stream;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+use tokio::io::AsyncRead;
+
+impl<R: AsyncRead + Unpin> Jsonl<R> {
+    /// Yield every record as raw bytes, without allocating a `String` per line.
+    ///
+    /// Records are split off the read buffer without copying and go through
+    /// the same trimming, comment, blank line and UTF-8 handling as the
+    /// `String` stream.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use async_jsonl::Jsonl;
+    /// use futures::StreamExt;
+    /// use serde_json::Value;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> anyhow::Result<()> {
+    ///     let mut records = Jsonl::from_path("data.jsonl").await?.bytes();
+    ///
+    ///     while let Some(record) = records.next().await {
+    ///         let value: Value = serde_json::from_slice(&record?)?;
+    ///         println!("{}", value);
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn bytes(self) -> ByteLines<R> {
+        ByteLines { inner: self }
+    }
+}
+
+/// Stream of raw records, created by [`Jsonl::bytes`].
+pub struct ByteLines<R> {
+    inner: Jsonl<R>,
+}
+
+impl<R: AsyncRead + Unpin> Stream for ByteLines<R> {
+    type Item = Result<Bytes, JsonlError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let inner = &mut self.inner;
+        loop {
+            let location = match ready!(inner.lines.poll_next_line(cx)) {
+                Ok(Some(location)) => location,
+                Ok(None) => return Poll::Ready(None), // EOF
+                Err(e) => return Poll::Ready(Some(Err(e.into()))),
+            };
+            let record = match inner.process_line(location) {
+                LineAction::Borrowed(line) => {
+                    let start = line.as_ptr() as usize - inner.lines.line().as_ptr() as usize;
+                    let end = start + line.len();
+                    inner.lines.take(start..end)
+                }
+                LineAction::Owned(line) => Bytes::from(line),
+                LineAction::Skip => continue,
+                LineAction::Reject(e) => return Poll::Ready(Some(Err(e))),
+            };
+            return Poll::Ready(Some(Ok(record)));
+        }
+    }
+}
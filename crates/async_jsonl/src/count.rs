@@ -0,0 +1,260 @@
+use crate::builder::{BlankLinePolicy, LineAction, ReadOptions, Utf8Policy};
+use crate::encoding::UTF8_BOM;
+use crate::{Jsonl, JsonlError};
+use futures::future::poll_fn;
+use serde::de::IgnoredAny;
+use tokio::io::{AsyncBufReadExt, AsyncRead};
+
+/// Breakdown of the lines of an input, returned by
+/// [`JsonlReader::count_detailed`](crate::JsonlReader::count_detailed).
+///
+/// Every line falls into exactly one category, so `total` is the sum of the
+/// other counts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct CountReport {
+    /// Number of lines, including blank and comment lines.
+    pub total: usize,
+    /// Lines that are empty or contain only whitespace.
+    pub blank: usize,
+    /// Lines starting with one of the configured comment prefixes.
+    pub comments: usize,
+    /// Lines holding a valid JSON value.
+    pub valid: usize,
+    /// Lines that are not valid JSON, including lines that are not valid
+    /// UTF-8 or exceed the configured length limit.
+    pub invalid: usize,
+}
+
+impl<R: AsyncRead + Unpin> Jsonl<R> {
+    /// Count records by scanning raw buffers for newlines.
+    ///
+    /// Lines are neither copied nor validated: blank lines and comments are
+    /// recognised from their first bytes, everything else counts as a record.
+    pub(crate) async fn count_records(mut self) -> Result<usize, JsonlError> {
+        if self.lines.is_limited()
+            || self.lines.is_json_seq()
+            || matches!(self.options.utf8, Utf8Policy::Skip)
+        {
+            // The raw scan cannot tell where a range ends, split sequences,
+            // nor see which lines are dropped for their encoding
+            return self.count_processed().await;
+        }
+        if self.lines.line_number() > 0 {
+            // Only the first line loses a byte order mark, and the raw scan
+            // cannot tell whether it is at the first line
+            return self.count_processed().await;
+        }
+        let head = self.lines.get_mut().fill_buf().await?;
+        if !head.is_empty() && head.len() < UTF8_BOM.len() && UTF8_BOM.starts_with(head) {
+            // A byte order mark split across buffers
+            return self.count_processed().await;
+        }
+
+        let mut tally = Tally::new(&self.options);
+        let reader = self.lines.get_mut();
+        loop {
+            let chunk = reader.fill_buf().await?;
+            if chunk.is_empty() {
+                break;
+            }
+            let len = chunk.len();
+            tally.feed(chunk);
+            reader.consume(len);
+        }
+        tally.finish();
+
+        Ok(match self.options.blank_lines {
+            BlankLinePolicy::Yield => tally.records + tally.blank,
+            BlankLinePolicy::Skip | BlankLinePolicy::Error => tally.records,
+        })
+    }
+
+    /// Count records by reading every line as the stream would, without
+    /// parsing them.
+    async fn count_processed(mut self) -> Result<usize, JsonlError> {
+        let mut records = 0;
+        while let Some(location) = poll_fn(|cx| self.lines.poll_next_line(cx)).await? {
+            match self.process_line(location) {
+                LineAction::Skip | LineAction::Reject(JsonlError::BlankLine { .. }) => {}
+                _ => records += 1,
+            }
+        }
+        Ok(records)
+    }
+
+    /// Classify every line, checking records for valid JSON without building values.
+    pub(crate) async fn count_lines_detailed(mut self) -> Result<CountReport, JsonlError> {
+        let mut report = CountReport::default();
+        while poll_fn(|cx| self.lines.poll_next_line(cx)).await?.is_some() {
+            report.total += 1;
+            let line = self.lines.line();
+            // Whitespace is recognised as the stream recognises it
+            let (blank, start, record) = match std::str::from_utf8(line) {
+                Ok(text) => (
+                    text.trim().is_empty(),
+                    text.trim_start().as_bytes(),
+                    self.options.apply_trim(text).as_bytes(),
+                ),
+                Err(_) => (line.trim_ascii().is_empty(), line.trim_ascii_start(), line),
+            };
+            if blank {
+                report.blank += 1;
+            } else if is_comment(&self.options, start) {
+                report.comments += 1;
+            } else if !self.lines.overflowed() && !self.lines.truncated() && is_json(record) {
+                report.valid += 1;
+            } else {
+                report.invalid += 1;
+            }
+        }
+        Ok(report)
+    }
+}
+
+/// Whether `line` is valid UTF-8 holding a single JSON value.
+pub(crate) fn is_json(line: &[u8]) -> bool {
+    // Ignored strings are skipped without checking their encoding
+    std::str::from_utf8(line).is_ok_and(|line| serde_json::from_str::<IgnoredAny>(line).is_ok())
+}
+
+fn is_comment(options: &ReadOptions, line: &[u8]) -> bool {
+    options
+        .comment_prefixes
+        .iter()
+        .any(|prefix| line.starts_with(prefix.as_bytes()))
+}
+
+/// Counts lines fed to it in arbitrary chunks.
+struct Tally<'a> {
+    options: &'a ReadOptions,
+    /// First bytes of the current line after leading whitespace, enough to
+    /// match the longest comment prefix
+    head: Vec<u8>,
+    /// Start of a non-ASCII character in the leading whitespace, cut off at
+    /// the end of the last chunk
+    pending: Vec<u8>,
+    head_limit: usize,
+    /// Whether no byte was fed yet, so a byte order mark is dropped
+    at_start: bool,
+    /// Whether the current line has any byte yet
+    started: bool,
+    /// Whether the current line has anything besides whitespace
+    content: bool,
+    records: usize,
+    blank: usize,
+}
+
+impl<'a> Tally<'a> {
+    fn new(options: &'a ReadOptions) -> Self {
+        let head_limit = options
+            .comment_prefixes
+            .iter()
+            .map(String::len)
+            .max()
+            .unwrap_or(0);
+        Self {
+            options,
+            head: Vec::with_capacity(head_limit),
+            pending: Vec::new(),
+            head_limit,
+            at_start: true,
+            started: false,
+            content: false,
+            records: 0,
+            blank: 0,
+        }
+    }
+
+    fn feed(&mut self, mut chunk: &[u8]) {
+        if self.at_start {
+            self.at_start = false;
+            chunk = chunk.strip_prefix(UTF8_BOM).unwrap_or(chunk);
+        }
+        while !chunk.is_empty() {
+            self.started = true;
+            if !self.content {
+                let Some(start) = self.skip_whitespace(chunk) else {
+                    return;
+                };
+                if !self.content {
+                    self.end_line();
+                    chunk = &chunk[start + 1..];
+                    continue;
+                }
+                chunk = &chunk[start..];
+            }
+
+            if self.head.len() < self.head_limit {
+                let take = (self.head_limit - self.head.len()).min(chunk.len());
+                self.head.extend_from_slice(&chunk[..take]);
+            }
+
+            match memchr::memchr(b'\n', chunk) {
+                Some(newline) => {
+                    self.end_line();
+                    chunk = &chunk[newline + 1..];
+                }
+                None => return,
+            }
+        }
+    }
+
+    /// Skip whitespace at the start of the current line as `str::trim_start`
+    /// would, returning where the content or the end of the line starts in
+    /// `chunk`, or `None` when all of it is whitespace.
+    fn skip_whitespace(&mut self, chunk: &[u8]) -> Option<usize> {
+        for (i, &byte) in chunk.iter().enumerate() {
+            if !self.pending.is_empty() && byte & 0xC0 != 0x80 {
+                // A character cut short is invalid, not whitespace
+                return Some(self.start_content(i));
+            }
+            if byte == b'\n' {
+                return Some(i);
+            }
+            if byte.is_ascii() {
+                if !char::from(byte).is_whitespace() {
+                    return Some(self.start_content(i));
+                }
+                continue;
+            }
+            self.pending.push(byte);
+            match std::str::from_utf8(&self.pending) {
+                Ok(c) if c.chars().all(char::is_whitespace) => self.pending.clear(),
+                Err(e) if e.error_len().is_none() => {}
+                _ => return Some(self.start_content(i + 1)),
+            }
+        }
+        None
+    }
+
+    /// Mark the current line as having content from `start` in the chunk on,
+    /// after any character held in `pending`.
+    fn start_content(&mut self, start: usize) -> usize {
+        self.content = true;
+        self.head.append(&mut self.pending);
+        self.head.truncate(self.head_limit);
+        start
+    }
+
+    /// Account for a last line without a terminator.
+    fn finish(&mut self) {
+        if !self.pending.is_empty() {
+            self.start_content(0);
+        }
+        if self.started {
+            self.end_line();
+        }
+    }
+
+    fn end_line(&mut self) {
+        if !self.content {
+            self.blank += 1;
+        } else if !is_comment(self.options, &self.head) {
+            self.records += 1;
+        }
+        self.head.clear();
+        self.started = false;
+        self.content = false;
+    }
+}
@@ -0,0 +1,187 @@
+use crate::{JsonlError, JsonlWriter};
+use futures::lock::Mutex;
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use tokio::fs::File;
+use tokio::io::BufWriter;
+
+/// When a [`DurableJsonlWriter`] makes appended records durable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Durability {
+    /// Leave records in the writer's buffers; they reach the file when
+    /// synced or once the buffers fill up.
+    #[default]
+    None,
+    /// Hand every record to the operating system before returning, which
+    /// survives the process crashing but not the machine.
+    Flush,
+    /// Sync to disk after every `n` records.
+    FsyncEveryN(usize),
+    /// Sync to disk on the first append at least this long after the last
+    /// sync. Nothing syncs in between, so call
+    /// [`sync`](DurableJsonlWriter::sync) before going idle.
+    FsyncInterval(Duration),
+    /// Every append returns once its record is on disk, and appends waiting
+    /// at the same time share one sync.
+    GroupCommit,
+}
+
+/// A JSONL file shared by concurrent appenders, which makes records durable
+/// according to its [`Durability`].
+///
+/// Created by [`JsonlWriter::durability`]. Appends take `&self`, so the
+/// writer can be shared behind an `Arc` by tasks appending at the same
+/// time. Syncing goes through a second handle to the file, so records keep
+/// being written while a sync is running, and the next sync covers all of
+/// them.
+///
+/// # Examples
+///
+/// ```ignore
+/// use async_jsonl::{Durability, JsonlWriter, TornTailPolicy};
+/// use serde_json::json;
+/// use std::sync::Arc;
+///
+/// #[tokio::main]
+/// async fn main() -> anyhow::Result<()> {
+///     let log = JsonlWriter::open_append("audit.jsonl", TornTailPolicy::Truncate)
+///         .await?
+///         .durability(Durability::GroupCommit)
+///         .await?;
+///     let log = Arc::new(log);
+///
+///     let tasks: Vec<_> = (0..8)
+///         .map(|user| {
+///             let log = log.clone();
+///             tokio::spawn(async move {
+///                 // Resolves once the record is on disk
+///                 log.append(&json!({"user": user, "action": "login"})).await
+///             })
+///         })
+///         .collect();
+///     for task in tasks {
+///         task.await??;
+///     }
+///     Ok(())
+/// }
+/// ```
+pub struct DurableJsonlWriter {
+    durability: Durability,
+    writing: Mutex<Writing>,
+    /// Second handle to the file, held while syncing
+    syncing: Mutex<File>,
+    /// Number of records handed to the operating system
+    flushed: AtomicU64,
+    /// Number of records known to be on disk
+    synced: AtomicU64,
+    created: Instant,
+    /// When the last sync started, in nanoseconds since `created`
+    last_sync: AtomicU64,
+}
+
+struct Writing {
+    writer: JsonlWriter<BufWriter<File>>,
+    /// Number of records appended
+    appended: u64,
+}
+
+impl JsonlWriter<BufWriter<File>> {
+    /// Share this writer between concurrent appenders, making records
+    /// durable as `durability` says.
+    pub async fn durability(
+        self,
+        durability: Durability,
+    ) -> Result<DurableJsonlWriter, JsonlError> {
+        let syncing = self.get_ref().get_ref().try_clone().await?;
+        Ok(DurableJsonlWriter {
+            durability,
+            writing: Mutex::new(Writing {
+                writer: self,
+                appended: 0,
+            }),
+            syncing: Mutex::new(syncing),
+            flushed: AtomicU64::new(0),
+            synced: AtomicU64::new(0),
+            created: Instant::now(),
+            last_sync: AtomicU64::new(0),
+        })
+    }
+}
+
+impl DurableJsonlWriter {
+    /// Serialize `record` and append it, returning once it is as durable as
+    /// the writer's [`Durability`] asks.
+    ///
+    /// A record whose write or flush fails is not counted as appended, so it
+    /// never counts as synced, but bytes of it left in the buffer may still
+    /// reach the file along with later records.
+    pub async fn append<T: Serialize + ?Sized>(&self, record: &T) -> Result<(), JsonlError> {
+        let mut writing = self.writing.lock().await;
+        writing.writer.write(record).await?;
+        if self.durability == Durability::None {
+            writing.appended += 1;
+            return Ok(());
+        }
+        writing.writer.flush().await?;
+        writing.appended += 1;
+        let appended = writing.appended;
+        self.flushed.store(appended, Ordering::Release);
+        drop(writing);
+
+        let sync = match self.durability {
+            Durability::None | Durability::Flush => false,
+            Durability::FsyncEveryN(n) => {
+                // Others may have synced past this record since it was written
+                let synced = self.synced.load(Ordering::Acquire);
+                appended.saturating_sub(synced) >= n.max(1) as u64
+            }
+            Durability::FsyncInterval(interval) => {
+                let last_sync = Duration::from_nanos(self.last_sync.load(Ordering::Acquire));
+                self.created.elapsed() >= last_sync + interval
+            }
+            Durability::GroupCommit => true,
+        };
+        if sync {
+            self.sync_through(appended).await?;
+        }
+        Ok(())
+    }
+
+    /// Sync every record appended so far to disk.
+    pub async fn sync(&self) -> Result<(), JsonlError> {
+        let mut writing = self.writing.lock().await;
+        writing.writer.flush().await?;
+        let appended = writing.appended;
+        self.flushed.store(appended, Ordering::Release);
+        drop(writing);
+        self.sync_through(appended).await
+    }
+
+    /// Number of records known to be on disk.
+    pub fn synced(&self) -> u64 {
+        self.synced.load(Ordering::Acquire)
+    }
+
+    /// Sync every record appended so far, returning the underlying writer.
+    pub async fn finish(self) -> Result<JsonlWriter<BufWriter<File>>, JsonlError> {
+        self.sync().await?;
+        Ok(self.writing.into_inner().writer)
+    }
+
+    /// Make sure the first `appended` records are on disk, along with any
+    /// others flushed by then.
+    async fn sync_through(&self, appended: u64) -> Result<(), JsonlError> {
+        let file = self.syncing.lock().await;
+        // Whoever synced while we waited may have covered our records
+        if self.synced.load(Ordering::Acquire) >= appended {
+            return Ok(());
+        }
+        let flushed = self.flushed.load(Ordering::Acquire);
+        self.last_sync
+            .store(self.created.elapsed().as_nanos() as u64, Ordering::Release);
+        file.sync_data().await?;
+        self.synced.fetch_max(flushed, Ordering::AcqRel);
+        Ok(())
+    }
+}
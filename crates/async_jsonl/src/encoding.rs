@@ -0,0 +1,211 @@
+use crate::Encoding;
+use std::io;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+use tokio::io::{AsyncRead, ReadBuf};
+
+/// Byte order mark of UTF-8 text.
+pub(crate) const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// Byte written in place of UTF-16 that does not decode, so that the line
+/// goes through the UTF-8 policy like any other invalid text.
+const INVALID: u8 = 0xFF;
+
+/// Reader handing out its input as UTF-8, transcoding UTF-16 on the fly.
+pub(crate) struct Decoder<R> {
+    inner: R,
+    state: State,
+    /// Raw bytes read from `inner`, for UTF-16 input
+    raw: Vec<u8>,
+    /// Decoded bytes not handed out yet
+    out: Vec<u8>,
+    out_pos: usize,
+}
+
+enum State {
+    /// Reading the first bytes to look for a UTF-16 byte order mark
+    Detect {
+        head: [u8; 2],
+        filled: usize,
+    },
+    Utf8,
+    Utf16(Utf16Decoder),
+}
+
+impl<R> Decoder<R> {
+    pub(crate) fn new(inner: R, encoding: Encoding, capacity: usize) -> Self {
+        let state = match encoding {
+            Encoding::Utf8 => State::Utf8,
+            Encoding::Utf16Le => State::Utf16(Utf16Decoder::new(false)),
+            Encoding::Utf16Be => State::Utf16(Utf16Decoder::new(true)),
+            Encoding::Auto => State::Detect {
+                head: [0; 2],
+                filled: 0,
+            },
+        };
+        let raw = match state {
+            State::Utf8 => Vec::new(),
+            _ => vec![0; capacity.max(2)],
+        };
+        Self {
+            inner,
+            state,
+            raw,
+            out: Vec::new(),
+            out_pos: 0,
+        }
+    }
+
+    pub(crate) fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for Decoder<R> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        loop {
+            if this.out_pos < this.out.len() {
+                let len = buf.remaining().min(this.out.len() - this.out_pos);
+                buf.put_slice(&this.out[this.out_pos..this.out_pos + len]);
+                this.out_pos += len;
+                if this.out_pos == this.out.len() {
+                    this.out.clear();
+                    this.out_pos = 0;
+                }
+                return Poll::Ready(Ok(()));
+            }
+
+            match &mut this.state {
+                State::Utf8 => return Pin::new(&mut this.inner).poll_read(cx, buf),
+                State::Detect { head, filled } => {
+                    let mut head_buf = ReadBuf::new(&mut head[*filled..]);
+                    ready!(Pin::new(&mut this.inner).poll_read(cx, &mut head_buf))?;
+                    let read = head_buf.filled().len();
+                    *filled += read;
+                    if read > 0 && *filled < head.len() {
+                        continue;
+                    }
+
+                    // The bytes looked at are still part of the input. A byte
+                    // order mark goes on as a UTF-8 one, dropped with the first line
+                    this.state = match &head[..*filled] {
+                        [0xFF, 0xFE] => {
+                            this.out.extend_from_slice(UTF8_BOM);
+                            State::Utf16(Utf16Decoder::new(false))
+                        }
+                        [0xFE, 0xFF] => {
+                            this.out.extend_from_slice(UTF8_BOM);
+                            State::Utf16(Utf16Decoder::new(true))
+                        }
+                        head => {
+                            this.out.extend_from_slice(head);
+                            State::Utf8
+                        }
+                    };
+                }
+                State::Utf16(decoder) => {
+                    let mut raw = ReadBuf::new(&mut this.raw);
+                    ready!(Pin::new(&mut this.inner).poll_read(cx, &mut raw))?;
+                    if raw.filled().is_empty() {
+                        decoder.finish(&mut this.out);
+                        if this.out.is_empty() {
+                            return Poll::Ready(Ok(())); // EOF
+                        }
+                        continue;
+                    }
+                    decoder.decode(raw.filled(), &mut this.out);
+                }
+            }
+        }
+    }
+}
+
+/// Decodes UTF-16 fed in arbitrary chunks into UTF-8.
+pub(crate) struct Utf16Decoder {
+    big_endian: bool,
+    /// First byte of a code unit split across chunks
+    odd: Option<u8>,
+    /// High surrogate waiting for the low one
+    high: Option<u16>,
+}
+
+impl Utf16Decoder {
+    pub(crate) fn new(big_endian: bool) -> Self {
+        Self {
+            big_endian,
+            odd: None,
+            high: None,
+        }
+    }
+
+    /// Append the UTF-8 encoding of `bytes` to `out`, holding back an
+    /// incomplete code unit or surrogate pair for the next chunk.
+    pub(crate) fn decode(&mut self, mut bytes: &[u8], out: &mut Vec<u8>) {
+        if let Some(first) = self.odd.take() {
+            let Some((&second, rest)) = bytes.split_first() else {
+                self.odd = Some(first);
+                return;
+            };
+            self.push_unit([first, second], out);
+            bytes = rest;
+        }
+
+        let mut units = bytes.chunks_exact(2);
+        for unit in &mut units {
+            self.push_unit([unit[0], unit[1]], out);
+        }
+        if let [odd] = units.remainder() {
+            self.odd = Some(*odd);
+        }
+    }
+
+    /// Mark an incomplete code unit or surrogate pair left at the end of the input as invalid.
+    pub(crate) fn finish(&mut self, out: &mut Vec<u8>) {
+        if self.odd.take().is_some() | self.high.take().is_some() {
+            out.push(INVALID);
+        }
+    }
+
+    fn push_unit(&mut self, bytes: [u8; 2], out: &mut Vec<u8>) {
+        let unit = if self.big_endian {
+            u16::from_be_bytes(bytes)
+        } else {
+            u16::from_le_bytes(bytes)
+        };
+
+        if let Some(high) = self.high.take() {
+            if (0xDC00..0xE000).contains(&unit) {
+                let code =
+                    0x10000 + ((u32::from(high) - 0xD800) << 10) + (u32::from(unit) - 0xDC00);
+                push_char(code, out);
+                return;
+            }
+            out.push(INVALID);
+        }
+        match unit {
+            0xD800..=0xDBFF => self.high = Some(unit),
+            0xDC00..=0xDFFF => out.push(INVALID),
+            _ => push_char(u32::from(unit), out),
+        }
+    }
+}
+
+fn push_char(code: u32, out: &mut Vec<u8>) {
+    match char::from_u32(code) {
+        Some(c) => out.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes()),
+        None => out.push(INVALID),
+    }
+}
+
+/// Decode a whole line of UTF-16 into `out`, replacing its previous content.
+pub(crate) fn decode_utf16_line(line: &[u8], big_endian: bool, out: &mut Vec<u8>) {
+    out.clear();
+    let mut decoder = Utf16Decoder::new(big_endian);
+    decoder.decode(line, out);
+    decoder.finish(out);
+}
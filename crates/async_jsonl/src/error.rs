@@ -0,0 +1,269 @@
+use std::fmt;
+
+/// Maximum number of bytes of an offending line kept in an error.
+const SNIPPET_LIMIT: usize = 80;
+
+/// Where a line sits in the input.
+///
+/// Forward readers know both the line number and the byte offset. Readers that
+/// walk the input backwards (`last_n`) only know the byte offset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Location {
+    /// 1-based line number, counting blank lines.
+    pub line: Option<u64>,
+    /// Byte offset of the first byte of the line.
+    pub offset: Option<u64>,
+}
+
+impl Location {
+    pub(crate) fn new(line: u64, offset: u64) -> Self {
+        Self {
+            line: Some(line),
+            offset: Some(offset),
+        }
+    }
+
+    pub(crate) fn at_offset(offset: u64) -> Self {
+        Self {
+            line: None,
+            offset: Some(offset),
+        }
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.line, self.offset) {
+            (Some(line), Some(offset)) => write!(f, "line {} (byte {})", line, offset),
+            (Some(line), None) => write!(f, "line {}", line),
+            (None, Some(offset)) => write!(f, "byte {}", offset),
+            (None, None) => write!(f, "unknown position"),
+        }
+    }
+}
+
+/// Error produced while reading or deserializing JSONL data.
+///
+/// Errors tied to a particular line carry its [`Location`] and a truncated,
+/// lossily decoded copy of the offending text, so callers can report exactly
+/// where a bad record sits without parsing error messages.
+///
+/// # Examples
+///
+/// ```ignore
+/// use async_jsonl::{Jsonl, JsonlDeserialize, JsonlError};
+/// use futures::StreamExt;
+/// use serde_json::Value;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), JsonlError> {
+///     let mut values = Jsonl::from_path("data.jsonl").await?.deserialize::<Value>();
+///
+///     while let Some(result) = values.next().await {
+///         match result {
+///             Ok(value) => println!("{}", value),
+///             Err(JsonlError::Parse { location, snippet, .. }) => {
+///                 eprintln!("bad record at {}: {}", location, snippet)
+///             }
+///             Err(e) => return Err(e),
+///         }
+///     }
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum JsonlError {
+    /// The underlying reader failed.
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// A line is not valid JSON for the requested type.
+    #[error("Failed to parse JSON line at {location}: {source}")]
+    Parse {
+        location: Location,
+        snippet: String,
+        #[source]
+        source: serde_json::Error,
+    },
+
+    /// A field of a [`LazyRecord`](crate::LazyRecord) does not have the requested type,
+    /// or the JSON pointer used to find it is malformed.
+    #[error("Failed to read field {pointer:?} of the record at {location}: {source}")]
+    Field {
+        location: Location,
+        pointer: String,
+        #[source]
+        source: serde_json::Error,
+    },
+
+    /// A line is not valid UTF-8.
+    #[error("Invalid UTF-8 at {location}: {source}")]
+    InvalidUtf8 {
+        location: Location,
+        snippet: String,
+        #[source]
+        source: std::str::Utf8Error,
+    },
+
+    /// A line is longer than the configured limit.
+    #[error("Line at {location} exceeds the limit of {limit} bytes")]
+    LineTooLong {
+        location: Location,
+        limit: usize,
+        snippet: String,
+    },
+
+    /// A record of an RFC 7464 JSON text sequence was cut short, or the last
+    /// record of a file opened for appending was torn by an interrupted write.
+    #[error("Truncated JSON text at {location}")]
+    Truncated { location: Location, snippet: String },
+
+    /// A record's tag has no registered type and no fallback was set, or the
+    /// record has no tag at all.
+    #[error("No handler for tag {tag:?} of the record at {location}")]
+    UnknownTag {
+        location: Location,
+        tag: Option<String>,
+    },
+
+    /// A record's tag is not a string, so it has no registered type, and no
+    /// fallback was set.
+    #[error("Tag {value} of the record at {location} is not a string")]
+    InvalidTag { location: Location, value: String },
+
+    /// The input ended before its header record.
+    #[error("Missing header record")]
+    MissingHeader,
+
+    /// A record could not be serialized.
+    #[error("Failed to serialize record: {0}")]
+    Serialize(#[source] serde_json::Error),
+
+    /// A blank line was found while blank lines are configured as errors.
+    #[error("Unexpected blank line at {location}")]
+    BlankLine { location: Location },
+
+    /// Every error skipped under [`ErrorPolicy::Collect`](crate::ErrorPolicy::Collect),
+    /// reported once the input is exhausted.
+    #[error("{} lines failed to read or deserialize", .0.len())]
+    Collected(Vec<JsonlError>),
+
+    /// The sink of [`ErrorPolicy::DeadLetter`](crate::ErrorPolicy::DeadLetter) failed.
+    #[error("Dead letter sink failed: {0}")]
+    DeadLetter(#[source] Box<dyn std::error::Error + Send + Sync>),
+}
+
+impl JsonlError {
+    pub(crate) fn parse(location: Location, line: &[u8], source: serde_json::Error) -> Self {
+        Self::Parse {
+            location,
+            snippet: snippet(line),
+            source,
+        }
+    }
+
+    pub(crate) fn line_too_long(location: Location, limit: usize, line: &[u8]) -> Self {
+        Self::LineTooLong {
+            location,
+            limit,
+            snippet: snippet(line),
+        }
+    }
+
+    pub(crate) fn truncated(location: Location, line: &[u8]) -> Self {
+        Self::Truncated {
+            location,
+            snippet: snippet(line),
+        }
+    }
+
+    pub(crate) fn invalid_utf8(
+        location: Location,
+        line: &[u8],
+        source: std::str::Utf8Error,
+    ) -> Self {
+        Self::InvalidUtf8 {
+            location,
+            snippet: snippet(line),
+            source,
+        }
+    }
+
+    /// Location of the offending line, if the error is tied to one.
+    pub fn location(&self) -> Option<Location> {
+        match self {
+            Self::Io(_)
+            | Self::MissingHeader
+            | Self::Serialize(_)
+            | Self::Collected(_)
+            | Self::DeadLetter(_) => None,
+            Self::Parse { location, .. }
+            | Self::Field { location, .. }
+            | Self::InvalidUtf8 { location, .. }
+            | Self::LineTooLong { location, .. }
+            | Self::Truncated { location, .. }
+            | Self::UnknownTag { location, .. }
+            | Self::InvalidTag { location, .. }
+            | Self::BlankLine { location } => Some(*location),
+        }
+    }
+
+    /// 1-based line number of the offending line, if known.
+    pub fn line(&self) -> Option<u64> {
+        self.location().and_then(|location| location.line)
+    }
+
+    /// Byte offset of the start of the offending line, if known.
+    pub fn offset(&self) -> Option<u64> {
+        self.location().and_then(|location| location.offset)
+    }
+
+    /// Truncated copy of the offending line, if the error is tied to one.
+    pub fn snippet(&self) -> Option<&str> {
+        match self {
+            Self::Io(_)
+            | Self::Field { .. }
+            | Self::BlankLine { .. }
+            | Self::UnknownTag { .. }
+            | Self::InvalidTag { .. }
+            | Self::MissingHeader
+            | Self::Serialize(_)
+            | Self::Collected(_)
+            | Self::DeadLetter(_) => None,
+            Self::Parse { snippet, .. }
+            | Self::InvalidUtf8 { snippet, .. }
+            | Self::LineTooLong { snippet, .. }
+            | Self::Truncated { snippet, .. } => Some(snippet),
+        }
+    }
+}
+
+/// Lossily decodes at most [`SNIPPET_LIMIT`] bytes of `line`.
+pub(crate) fn snippet(line: &[u8]) -> String {
+    if line.len() <= SNIPPET_LIMIT {
+        return String::from_utf8_lossy(line).into_owned();
+    }
+    let mut snippet = String::from_utf8_lossy(&line[..SNIPPET_LIMIT]).into_owned();
+    // A multi-byte character cut in half decodes as a replacement character.
+    if snippet.ends_with(char::REPLACEMENT_CHARACTER) {
+        snippet.pop();
+    }
+    snippet.push_str("...");
+    snippet
+}
+
+/// Adapter turning JSONL streams into streams of `anyhow::Result`.
+///
+/// `JsonlError` already converts into `anyhow::Error` through `?`; this trait
+/// covers code that consumes whole streams of `anyhow::Result<T>`.
+#[cfg(feature = "anyhow")]
+pub trait AnyhowStreamExt<T>: futures::Stream<Item = Result<T, JsonlError>> + Sized {
+    /// Map every error of the stream into an `anyhow::Error`.
+    fn into_anyhow(self) -> impl futures::Stream<Item = anyhow::Result<T>> {
+        futures::StreamExt::map(self, |result| result.map_err(anyhow::Error::from))
+    }
+}
+
+#[cfg(feature = "anyhow")]
+impl<T, S: futures::Stream<Item = Result<T, JsonlError>>> AnyhowStreamExt<T> for S {}
@@ -0,0 +1,486 @@
+use crate::builder::{LongLinePolicy, ReadOptions, Utf8Policy};
+use crate::count::is_json;
+use crate::encoding::{Decoder, UTF8_BOM};
+use crate::line_reader::{truncate_line, LineStream};
+use crate::take_n::TakeNLinesReverse;
+use crate::{CountReport, JsonlError, JsonlReader, Location, Positioned};
+use futures::future::poll_fn;
+use futures::Stream;
+use std::io;
+use std::ops::Range;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+use tokio::fs::File;
+use tokio::io::{AsyncBufRead, AsyncRead, BufReader};
+
+/// Reader for JSON values written back to back rather than one per line.
+///
+/// Value boundaries are found by tracking the nesting of braces and brackets
+/// outside of strings, so values may be pretty-printed over many lines, share
+/// a line, or follow each other with no whitespace at all. Everything else
+/// works like with [`Jsonl`](crate::Jsonl): the stream yields the text of
+/// each value, and [`JsonlReader`], [`JsonlDeserialize`](crate::JsonlDeserialize)
+/// and [`JsonlValueDeserialize`](crate::JsonlValueDeserialize) count, select
+/// and deserialize values instead of lines.
+///
+/// Errors carry the line number and byte offset where the value starts. Text
+/// that is not JSON is yielded as values too, delimited the same way, so a
+/// bad value surfaces as a parse error and reading carries on after it.
+///
+/// Created from a [`JsonlBuilder`](crate::JsonlBuilder) with
+/// [`json_stream`](crate::JsonlBuilder::json_stream), the maximum line length
+/// limits the size of every value instead, so a value that never closes
+/// cannot exhaust memory: bytes past the limit are dropped, and the value is
+/// reported as [`JsonlError::LineTooLong`] once it ends.
+///
+/// # Examples
+///
+/// ```ignore
+/// use async_jsonl::{JsonStream, JsonlDeserialize};
+/// use futures::StreamExt;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Event {
+///     id: u64,
+/// }
+///
+/// #[tokio::main]
+/// async fn main() -> anyhow::Result<()> {
+///     let mut events = JsonStream::from_path("events.json").await?.deserialize::<Event>();
+///
+///     while let Some(event) = events.next().await {
+///         println!("{}", event?.id);
+///     }
+///     Ok(())
+/// }
+/// ```
+pub struct JsonStream<R> {
+    reader: BufReader<Decoder<R>>,
+    scanner: Scanner,
+    options: ReadOptions,
+    /// Number of values read so far.
+    index: u64,
+    /// Number of values after which reading stops, if it stops before EOF.
+    end: Option<u64>,
+}
+
+impl<R: AsyncRead + Unpin> JsonStream<R> {
+    pub fn new(reader: R) -> Self {
+        Self::with_options(reader, ReadOptions::default())
+    }
+
+    pub(crate) fn with_options(reader: R, options: ReadOptions) -> Self {
+        let reader = Decoder::new(reader, options.encoding, options.buffer_capacity);
+        Self {
+            reader: BufReader::with_capacity(options.buffer_capacity, reader),
+            scanner: Scanner::new(options.max_line_length),
+            options,
+            index: 0,
+            end: None,
+        }
+    }
+
+    /// Yield every value together with the line number and byte offset where
+    /// it starts and its length in bytes.
+    pub fn with_positions(self) -> PositionedValues<R> {
+        PositionedValues { inner: self }
+    }
+
+    /// Consume the reader, returning the underlying source.
+    ///
+    /// Bytes read ahead into the buffer are lost.
+    pub fn into_inner(self) -> R {
+        self.reader.into_inner().into_inner()
+    }
+
+    /// Find the next value, keeping its text in the scanner when `keep` is set.
+    fn poll_value(
+        &mut self,
+        cx: &mut Context<'_>,
+        keep: bool,
+    ) -> Poll<io::Result<Option<Location>>> {
+        if self.end.is_some_and(|end| self.index >= end) {
+            return Poll::Ready(Ok(None));
+        }
+        loop {
+            let available = ready!(Pin::new(&mut self.reader).poll_fill_buf(cx))?;
+            if available.is_empty() {
+                // EOF: a value cut short is still handed out, to fail parsing
+                if !self.scanner.finish() {
+                    return Poll::Ready(Ok(None));
+                }
+                break;
+            }
+            let (used, done) = self.scanner.feed(available, keep);
+            Pin::new(&mut self.reader).consume(used);
+            if done {
+                break;
+            }
+        }
+        self.index += 1;
+        Poll::Ready(Ok(Some(self.scanner.location())))
+    }
+
+    fn poll_next_positioned(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Positioned<String>, JsonlError>>> {
+        loop {
+            let location = match ready!(self.poll_value(cx, true)) {
+                Ok(Some(location)) => location,
+                Ok(None) => return Poll::Ready(None),
+                Err(e) => return Poll::Ready(Some(Err(e.into()))),
+            };
+            let mut text = std::mem::take(&mut self.scanner.text);
+            if let Some(limit) = self.scanner.overflow() {
+                match self.options.long_lines {
+                    LongLinePolicy::Error => {
+                        let error = JsonlError::line_too_long(location, limit, &text);
+                        return Poll::Ready(Some(Err(error)));
+                    }
+                    LongLinePolicy::Truncate => {
+                        let kept = truncate_line(&text, limit).len();
+                        text.truncate(kept);
+                    }
+                }
+            }
+            let value = match String::from_utf8(text) {
+                Ok(value) => value,
+                Err(e) => match self.options.utf8 {
+                    Utf8Policy::Error => {
+                        let error =
+                            JsonlError::invalid_utf8(location, e.as_bytes(), e.utf8_error());
+                        return Poll::Ready(Some(Err(error)));
+                    }
+                    Utf8Policy::Lossy => String::from_utf8_lossy(e.as_bytes()).into_owned(),
+                    Utf8Policy::Skip => continue,
+                },
+            };
+            return Poll::Ready(Some(Ok(Positioned {
+                line: self.scanner.start_line,
+                offset: self.scanner.start_offset,
+                len: self.scanner.offset - self.scanner.start_offset,
+                value,
+            })));
+        }
+    }
+
+    /// Skip up to `n` values without keeping their text.
+    async fn skip(&mut self, n: u64) -> io::Result<()> {
+        for _ in 0..n {
+            if poll_fn(|cx| self.poll_value(cx, false)).await?.is_none() {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Stop reading once `values` more values have been read.
+    fn limit(&mut self, values: u64) {
+        let end = self.index.saturating_add(values);
+        self.end = Some(self.end.map_or(end, |current| current.min(end)));
+    }
+}
+
+impl JsonStream<File> {
+    /// Create a new reader from a file path
+    pub async fn from_path<P: AsRef<std::path::Path>>(path: P) -> Result<Self, JsonlError> {
+        let file = File::open(path).await?;
+        Ok(Self::new(file))
+    }
+}
+
+#[async_trait::async_trait]
+impl<R: AsyncRead + Unpin + Send> JsonlReader for JsonStream<R> {
+    type NLines = Self;
+    type NLinesRev = TakeNLinesReverse;
+
+    async fn first_n(mut self, n: usize) -> Result<Self::NLines, JsonlError> {
+        self.limit(n as u64);
+        Ok(self)
+    }
+
+    async fn skip_n(mut self, n: usize) -> Result<Self, JsonlError> {
+        self.skip(n as u64).await?;
+        Ok(self)
+    }
+
+    async fn range(mut self, range: Range<usize>) -> Result<Self, JsonlError> {
+        self.limit(range.end as u64);
+        self.skip(range.start as u64).await?;
+        Ok(self)
+    }
+
+    async fn nth(self, n: usize) -> Result<Self, JsonlError> {
+        self.range(n..n.saturating_add(1)).await
+    }
+
+    async fn last_n_buffered(self, n: usize) -> Result<Self::NLinesRev, JsonlError> {
+        TakeNLinesReverse::buffered(self, n).await
+    }
+
+    async fn count(mut self) -> Result<usize, JsonlError> {
+        // Values dropped for their encoding can only be told by their text
+        let skip_invalid = self.options.utf8 == Utf8Policy::Skip;
+        let mut count = 0;
+        while poll_fn(|cx| self.poll_value(cx, skip_invalid))
+            .await?
+            .is_some()
+        {
+            if !skip_invalid || std::str::from_utf8(&self.scanner.text).is_ok() {
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    async fn count_detailed(mut self) -> Result<CountReport, JsonlError> {
+        let mut report = CountReport::default();
+        while poll_fn(|cx| self.poll_value(cx, true)).await?.is_some() {
+            report.total += 1;
+            if self.scanner.overflow().is_none() && is_json(&self.scanner.text) {
+                report.valid += 1;
+            } else {
+                report.invalid += 1;
+            }
+        }
+        Ok(report)
+    }
+}
+
+impl<R: AsyncRead + Unpin> LineStream for JsonStream<R> {
+    fn poll_next_line(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<(String, Location), JsonlError>>> {
+        self.poll_next_positioned(cx).map(|value| {
+            value.map(|result| {
+                result.map(|value| {
+                    let location = value.location();
+                    (value.value, location)
+                })
+            })
+        })
+    }
+}
+
+impl<R: AsyncRead + Unpin> Stream for JsonStream<R> {
+    type Item = Result<String, JsonlError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.poll_next_line(cx)
+            .map(|value| value.map(|result| result.map(|(value, _)| value)))
+    }
+}
+
+/// Stream of values with their positions, created by [`JsonStream::with_positions`].
+pub struct PositionedValues<R> {
+    inner: JsonStream<R>,
+}
+
+impl<R: AsyncRead + Unpin> Stream for PositionedValues<R> {
+    type Item = Result<Positioned<String>, JsonlError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.poll_next_positioned(cx)
+    }
+}
+
+/// Where the current value stands in the input.
+#[derive(Clone, Copy)]
+enum Kind {
+    /// An object, array or string, which ends with a closing byte.
+    Nested,
+    /// A number or literal, which ends before whitespace or a structural byte.
+    Scalar,
+}
+
+/// Finds the boundaries of JSON values fed to it in arbitrary chunks.
+struct Scanner {
+    /// Text of the current value, when asked to keep it, up to `max_len` bytes
+    text: Vec<u8>,
+    max_len: Option<usize>,
+    kind: Option<Kind>,
+    /// Open braces and brackets of the current value
+    depth: usize,
+    in_string: bool,
+    escaped: bool,
+    /// Number of bytes of a UTF-8 byte order mark found at the start of the
+    /// input so far, until it is known whether the input starts with one
+    bom: Option<usize>,
+    /// 1-based line of the next byte
+    line: u64,
+    /// Byte offset of the next byte
+    offset: u64,
+    start_line: u64,
+    start_offset: u64,
+}
+
+impl Scanner {
+    fn new(max_len: Option<usize>) -> Self {
+        Self {
+            text: Vec::new(),
+            max_len,
+            kind: None,
+            depth: 0,
+            in_string: false,
+            escaped: false,
+            bom: Some(0),
+            line: 1,
+            offset: 0,
+            start_line: 1,
+            start_offset: 0,
+        }
+    }
+
+    /// Where the last value found starts.
+    fn location(&self) -> Location {
+        Location::new(self.start_line, self.start_offset)
+    }
+
+    /// The size limit, if the last value found exceeds it.
+    fn overflow(&self) -> Option<usize> {
+        self.max_len
+            .filter(|&limit| self.offset - self.start_offset > limit as u64)
+    }
+
+    /// Keep `bytes` of the current value, dropping those past the limit.
+    fn keep(&mut self, bytes: &[u8]) {
+        let room = match self.max_len {
+            Some(limit) => limit.saturating_sub(self.text.len()),
+            None => usize::MAX,
+        };
+        self.text.extend_from_slice(&bytes[..room.min(bytes.len())]);
+    }
+
+    /// Scan `chunk`, returning how many bytes were used and whether a value ended.
+    fn feed(&mut self, mut chunk: &[u8], keep: bool) -> (usize, bool) {
+        let mut skipped = 0;
+        if let Some(matched) = self.bom {
+            let rest = &UTF8_BOM[matched..];
+            let common = rest.iter().zip(chunk).take_while(|(a, b)| a == b).count();
+            if common == rest.len() {
+                self.bom = None;
+                self.offset = UTF8_BOM.len() as u64;
+                skipped = common;
+                chunk = &chunk[common..];
+            } else if common == chunk.len() {
+                // The byte order mark may go on in the next chunk
+                self.bom = Some(matched + common);
+                return (common, false);
+            } else {
+                // Bytes taken for a byte order mark start a value after all
+                self.bom = None;
+                self.scan(&UTF8_BOM[..matched], keep);
+            }
+        }
+        let (used, done) = self.scan(chunk, keep);
+        (skipped + used, done)
+    }
+
+    /// Scan `chunk` past any byte order mark, returning how many bytes were
+    /// used and whether a value ended.
+    fn scan(&mut self, chunk: &[u8], keep: bool) -> (usize, bool) {
+        // Where the kept part of the value starts in this chunk
+        let mut begin = 0;
+        for (i, &b) in chunk.iter().enumerate() {
+            let kind = match self.kind {
+                Some(kind) => kind,
+                None if is_whitespace(b) => {
+                    self.advance(b);
+                    continue;
+                }
+                None => self.start(b, i, &mut begin),
+            };
+            let end = match kind {
+                Kind::Scalar if is_delimiter(b) => i,
+                Kind::Scalar => {
+                    self.advance(b);
+                    continue;
+                }
+                Kind::Nested => {
+                    self.step(b);
+                    self.advance(b);
+                    if self.depth > 0 || self.in_string {
+                        continue;
+                    }
+                    i + 1
+                }
+            };
+            if keep {
+                self.keep(&chunk[begin..end]);
+            }
+            self.kind = None;
+            return (end, true);
+        }
+
+        if keep && self.kind.is_some() {
+            self.keep(&chunk[begin..]);
+        }
+        (chunk.len(), false)
+    }
+
+    /// Account for EOF, returning whether it ends a value.
+    fn finish(&mut self) -> bool {
+        if let Some(matched) = self.bom.take() {
+            // Input cut short in what looked like a byte order mark
+            self.scan(&UTF8_BOM[..matched], true);
+        }
+        let open = self.kind.is_some();
+        self.kind = None;
+        self.depth = 0;
+        self.in_string = false;
+        self.escaped = false;
+        open
+    }
+
+    fn start(&mut self, b: u8, i: usize, begin: &mut usize) -> Kind {
+        self.text.clear();
+        self.start_line = self.line;
+        self.start_offset = self.offset;
+        *begin = i;
+        let kind = match is_delimiter(b) {
+            true => Kind::Nested,
+            false => Kind::Scalar,
+        };
+        self.kind = Some(kind);
+        kind
+    }
+
+    fn step(&mut self, b: u8) {
+        if self.in_string {
+            if self.escaped {
+                self.escaped = false;
+            } else if b == b'\\' {
+                self.escaped = true;
+            } else if b == b'"' {
+                self.in_string = false;
+            }
+            return;
+        }
+        match b {
+            b'"' => self.in_string = true,
+            b'{' | b'[' => self.depth += 1,
+            // A stray closing byte makes a value of its own
+            b'}' | b']' => self.depth = self.depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+
+    fn advance(&mut self, b: u8) {
+        self.offset += 1;
+        if b == b'\n' {
+            self.line += 1;
+        }
+    }
+}
+
+pub(crate) fn is_whitespace(b: u8) -> bool {
+    matches!(b, b' ' | b'\t' | b'\n' | b'\r')
+}
+
+/// Whether `b` ends a scalar, or starts a value that is not one.
+fn is_delimiter(b: u8) -> bool {
+    is_whitespace(b) || matches!(b, b'{' | b'}' | b'[' | b']' | b'"')
+}
@@ -1,79 +1,441 @@
+use crate::builder::{Framing, LineAction, ReadOptions};
+use crate::encoding::Decoder;
+use crate::line_reader::{LineReader, LineStream, Source};
 use crate::take_n::{TakeNLines, TakeNLinesReverse};
-use crate::{Jsonl, JsonlReader};
+use crate::{
+    CountReport, Jsonl, JsonlDeserialize, JsonlError, JsonlReader, JsonlSeekReader, Location,
+    Positioned, PositionedLines, RawLines, RevLines,
+};
+use futures::future::poll_fn;
 use futures::{Stream, StreamExt};
+use serde::Deserialize;
+use std::ops::Range;
 use std::pin::Pin;
-use std::task::{Context, Poll};
+use std::task::{ready, Context, Poll};
 use tokio::fs::File;
-use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncSeek, BufReader};
+use tokio::io::{AsyncRead, AsyncSeek, BufReader};
 
 #[async_trait::async_trait]
-impl<R: AsyncRead + AsyncSeek + Unpin + Sync + Send> JsonlReader for Jsonl<R> {
+impl<R: AsyncRead + Unpin + Send> JsonlReader for Jsonl<R> {
     type NLines = TakeNLines<R>;
     type NLinesRev = TakeNLinesReverse;
 
-    async fn first_n(self, n: usize) -> anyhow::Result<Self::NLines> {
+    async fn first_n(self, n: usize) -> Result<Self::NLines, JsonlError> {
         Ok(self.get_n(n))
     }
 
-    async fn last_n(self, n: usize) -> anyhow::Result<Self::NLinesRev> {
-        self.get_rev_n(n).await
+    async fn skip_n(mut self, n: usize) -> Result<Self, JsonlError> {
+        self.lines.skip(n as u64).await?;
+        Ok(self)
+    }
+
+    async fn range(mut self, range: Range<usize>) -> Result<Self, JsonlError> {
+        self.lines.limit(range.end as u64);
+        self.lines.skip(range.start as u64).await?;
+        Ok(self)
+    }
+
+    async fn nth(self, n: usize) -> Result<Self, JsonlError> {
+        self.range(n..n.saturating_add(1)).await
+    }
+
+    async fn last_n_buffered(self, n: usize) -> Result<Self::NLinesRev, JsonlError> {
+        TakeNLinesReverse::buffered(self, n).await
     }
 
-    async fn count(self) -> usize {
-        StreamExt::count(self).await
+    async fn count(self) -> Result<usize, JsonlError> {
+        self.count_records().await
+    }
+
+    async fn count_detailed(self) -> Result<CountReport, JsonlError> {
+        self.count_lines_detailed().await
+    }
+}
+
+#[async_trait::async_trait]
+impl<R: AsyncRead + AsyncSeek + Unpin + Send> JsonlSeekReader for Jsonl<R> {
+    async fn last_n(self, n: usize) -> Result<Self::NLinesRev, JsonlError> {
+        self.get_rev_n(n).await
     }
 }
 
 impl<R: AsyncRead + Unpin> Jsonl<R> {
     pub fn new(file: R) -> Self {
-        let reader = BufReader::new(file);
+        Self::with_options(file, ReadOptions::default())
+    }
+
+    pub(crate) fn with_options(file: R, options: ReadOptions) -> Self {
+        let file = Decoder::new(file, options.encoding, options.buffer_capacity);
+        let reader = BufReader::with_capacity(options.buffer_capacity, file);
+        Self::with_source(Source::Buffered(reader), options)
+    }
+
+    pub(crate) fn with_source(reader: Source<R>, options: ReadOptions) -> Self {
+        let mut lines = LineReader::new(reader, options.max_line_length);
+        lines.set_json_seq(options.framing == Framing::JsonSeq);
         Self {
-            lines: reader.lines(),
+            lines,
+            options,
+            scratch: String::new(),
+            has_header: false,
         }
     }
 
     /// Get the first n lines from the beginning of the file
     pub(crate) fn get_n(self, n: usize) -> TakeNLines<R> {
-        let reader = self.lines.into_inner().into_inner();
-        TakeNLines::new(reader, n)
+        TakeNLines::new(self, n)
+    }
+
+    /// Yield every line together with its line number, byte offset and raw length.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use async_jsonl::Jsonl;
+    /// use futures::StreamExt;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> anyhow::Result<()> {
+    ///     let mut lines = Jsonl::from_path("events.jsonl").await?.with_positions();
+    ///
+    ///     while let Some(line) = lines.next().await {
+    ///         let line = line?;
+    ///         println!("line {} at byte {}: {}", line.line, line.offset, line.value);
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn with_positions(self) -> PositionedLines<R> {
+        PositionedLines::new(self)
+    }
+
+    /// Deserialize every line into `T`, keeping the position of each record.
+    pub fn deserialize_positioned<T>(self) -> impl Stream<Item = Result<Positioned<T>, JsonlError>>
+    where
+        T: for<'a> Deserialize<'a>,
+    {
+        self.with_positions().map(|result| {
+            result.and_then(|line| match serde_json::from_str::<T>(&line.value) {
+                Ok(value) => Ok(line.map(|_| value)),
+                Err(e) => Err(JsonlError::parse(line.location(), line.value.as_bytes(), e)),
+            })
+        })
+    }
+
+    /// Yield every line exactly as stored, with its terminator reported separately.
+    ///
+    /// Nothing is trimmed, decoded or dropped: blank and comment lines are
+    /// yielded too, invalid UTF-8 passes through and a byte order mark stays
+    /// at the start of the first line. Tools copying, splitting or checksumming
+    /// files can use it to leave untouched records exactly as they were. Lines
+    /// over [`JsonlBuilder::max_line_length`](crate::JsonlBuilder::max_line_length)
+    /// are always reported as errors, since they can't be yielded whole. UTF-16
+    /// input is yielded as the UTF-8 text it was transcoded to, and lines are
+    /// split at `\n` whatever the [`Framing`].
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use async_jsonl::Jsonl;
+    /// use futures::StreamExt;
+    /// use tokio::io::AsyncWriteExt;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> anyhow::Result<()> {
+    ///     let mut lines = Jsonl::from_path("events.jsonl").await?.raw_lines();
+    ///     let mut copy = tokio::fs::File::create("copy.jsonl").await?;
+    ///
+    ///     while let Some(line) = lines.next().await {
+    ///         let line = line?.value;
+    ///         copy.write_all(&line.content).await?;
+    ///         copy.write_all(line.terminator.as_bytes()).await?;
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn raw_lines(self) -> RawLines<R> {
+        RawLines::new(self)
+    }
+
+    /// Read the first record as a header of type `H`, leaving the reader
+    /// positioned on the first data record.
+    ///
+    /// Must be called before anything else is read. Blank and comment lines
+    /// before the header are skipped like anywhere else. Readers going
+    /// backwards, [`rev`](Jsonl::rev) and [`last_n`](crate::JsonlSeekReader::last_n),
+    /// then stop before the header instead of yielding it as a data record.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`JsonlError::MissingHeader`] if the input holds no record, and
+    /// the error of the first record if it fails to read or parse as `H`.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use async_jsonl::{Jsonl, JsonlSeekReader};
+    /// use futures::StreamExt;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Header {
+    ///     schema_version: u32,
+    /// }
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> anyhow::Result<()> {
+    ///     let mut reader = Jsonl::from_path("dataset.jsonl").await?;
+    ///     let header = reader.read_header::<Header>().await?;
+    ///     println!("schema version {}", header.schema_version);
+    ///
+    ///     // The header is never among the last records
+    ///     let mut last = reader.last_n(10).await?;
+    ///     while let Some(line) = last.next().await {
+    ///         println!("{}", line?);
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn read_header<H>(&mut self) -> Result<H, JsonlError>
+    where
+        H: for<'a> Deserialize<'a>,
+    {
+        let header = match poll_fn(|cx| self.poll_next_positioned(cx)).await {
+            Some(header) => header?,
+            None => return Err(JsonlError::MissingHeader),
+        };
+        self.has_header = true;
+        serde_json::from_str(&header.value)
+            .map_err(|e| JsonlError::parse(header.location(), header.value.as_bytes(), e))
+    }
+
+    /// Read the first record as a header of type `H`, then deserialize every
+    /// other record into `T`.
+    ///
+    /// See [`read_header`](Jsonl::read_header) for how the header is found and
+    /// how to read the data records backwards.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use async_jsonl::Jsonl;
+    /// use futures::StreamExt;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Header {
+    ///     producer: String,
+    /// }
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Sample {
+    ///     value: f64,
+    /// }
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> anyhow::Result<()> {
+    ///     let reader = Jsonl::from_path("dataset.jsonl").await?;
+    ///     let (header, mut samples) = reader.with_header::<Header, Sample>().await?;
+    ///
+    ///     println!("produced by {}", header.producer);
+    ///     while let Some(sample) = samples.next().await {
+    ///         println!("{}", sample?.value);
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn with_header<H, T>(
+        mut self,
+    ) -> Result<(H, impl Stream<Item = Result<T, JsonlError>>), JsonlError>
+    where
+        H: for<'a> Deserialize<'a>,
+        T: for<'a> Deserialize<'a>,
+    {
+        let header = self.read_header::<H>().await?;
+        Ok((header, self.deserialize::<T>()))
+    }
+
+    /// Deserialize the next record into `T`, which may borrow from the line buffer.
+    ///
+    /// Unlike [`JsonlDeserialize::deserialize`](crate::JsonlDeserialize::deserialize),
+    /// no `String` is allocated per line: the record is parsed straight out of
+    /// the reader's buffer, which is reused for the next line. Returns `None`
+    /// at the end of the input.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use async_jsonl::Jsonl;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct LogLine<'a> {
+    ///     level: &'a str,
+    ///     message: &'a str,
+    /// }
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> anyhow::Result<()> {
+    ///     let mut reader = Jsonl::from_path("app.log.jsonl").await?;
+    ///
+    ///     while let Some(line) = reader.next_borrowed::<LogLine>().await {
+    ///         let line = line?;
+    ///         if line.level == "error" {
+    ///             println!("{}", line.message);
+    ///         }
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn next_borrowed<'a, T>(&'a mut self) -> Option<Result<T, JsonlError>>
+    where
+        T: Deserialize<'a>,
+    {
+        let (record, location) = loop {
+            let location = match poll_fn(|cx| self.lines.poll_next_line(cx)).await {
+                Ok(Some(location)) => location,
+                Ok(None) => return None, // EOF
+                Err(e) => return Some(Err(e.into())),
+            };
+            // Only remember where the record is: returning the borrow from
+            // inside the loop would keep the reader borrowed for every line
+            match self.process_line(location) {
+                LineAction::Borrowed(line) => {
+                    let start = line.as_ptr() as usize - self.lines.line().as_ptr() as usize;
+                    break (Some(start..start + line.len()), location);
+                }
+                LineAction::Owned(line) => {
+                    self.scratch = line;
+                    break (None, location);
+                }
+                LineAction::Skip => continue,
+                LineAction::Reject(e) => return Some(Err(e)),
+            }
+        };
+
+        let line = match record {
+            Some(range) => std::str::from_utf8(&self.lines.line()[range])
+                .expect("record was already validated as UTF-8"),
+            None => self.scratch.as_str(),
+        };
+        Some(
+            serde_json::from_str(line).map_err(|e| JsonlError::parse(location, line.as_bytes(), e)),
+        )
+    }
+
+    pub(crate) fn poll_next_positioned(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Positioned<String>, JsonlError>>> {
+        loop {
+            let location = match ready!(self.lines.poll_next_line(cx)) {
+                Ok(Some(location)) => location,
+                Ok(None) => return Poll::Ready(None), // EOF
+                Err(e) => return Poll::Ready(Some(Err(e.into()))),
+            };
+            let value = match self.process_line(location) {
+                LineAction::Borrowed(line) => line.to_string(),
+                LineAction::Owned(line) => line,
+                LineAction::Skip => continue,
+                LineAction::Reject(e) => return Poll::Ready(Some(Err(e))),
+            };
+            return Poll::Ready(Some(Ok(Positioned {
+                line: self.lines.line_number(),
+                offset: self.lines.line_offset(),
+                len: self.lines.len(),
+                value,
+            })));
+        }
+    }
+
+    pub(crate) fn process_line(&self, location: Location) -> LineAction<'_> {
+        if self.lines.truncated() {
+            return LineAction::Reject(JsonlError::truncated(location, self.lines.line()));
+        }
+        self.options
+            .process(self.lines.line(), location, self.lines.overflowed())
     }
 }
 
 impl<R: AsyncRead + AsyncSeek + Unpin> Jsonl<R> {
-    /// Get the last n lines from the end of the file (like tail)
-    pub(crate) async fn get_rev_n(self, n: usize) -> anyhow::Result<TakeNLinesReverse> {
+    /// Yield lines from the end of the source back to its start, lazily.
+    ///
+    /// Unlike [`last_n`](crate::JsonlSeekReader::last_n), the number of lines
+    /// need not be known up front: the source is read backwards one buffer at
+    /// a time for as long as the stream is polled, so walking back until some
+    /// condition holds takes constant memory.
+    ///
+    /// After [`read_header`](Jsonl::read_header), the header is left out: the
+    /// stream ends before the first record of the source.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use async_jsonl::{Jsonl, JsonlDeserialize};
+    /// use futures::StreamExt;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Event {
+    ///     timestamp: u64,
+    /// }
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> anyhow::Result<()> {
+    ///     let mut events = Jsonl::from_path("events.jsonl").await?.rev().deserialize::<Event>();
+    ///
+    ///     // Walk back through the last hour only
+    ///     let cutoff = 1_760_000_000;
+    ///     while let Some(event) = events.next().await {
+    ///         let event = event?;
+    ///         if event.timestamp < cutoff {
+    ///             break;
+    ///         }
+    ///         println!("{}", event.timestamp);
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn rev(self) -> RevLines<R> {
         let reader = self.lines.into_inner().into_inner();
-        TakeNLinesReverse::new(reader, n).await
+        RevLines::new(reader, self.options, self.has_header)
+    }
+
+    /// Get the last n lines from the end of the file (like tail)
+    pub(crate) async fn get_rev_n(self, n: usize) -> Result<TakeNLinesReverse, JsonlError> {
+        TakeNLinesReverse::new(self.rev(), n).await
     }
 }
 
 impl Jsonl<File> {
     /// Create a new Jsonl reader from a file path
-    pub async fn from_path<P: AsRef<std::path::Path>>(path: P) -> anyhow::Result<Self> {
-        let file = File::open(path)
-            .await
-            .map_err(|e| anyhow::anyhow!("Failed to open file: {}", e))?;
+    pub async fn from_path<P: AsRef<std::path::Path>>(path: P) -> Result<Self, JsonlError> {
+        let file = File::open(path).await?;
         Ok(Self::new(file))
     }
 }
 
+impl<R: AsyncRead + Unpin> LineStream for Jsonl<R> {
+    fn poll_next_line(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<(String, Location), JsonlError>>> {
+        self.poll_next_positioned(cx).map(|line| {
+            line.map(|result| {
+                result.map(|line| {
+                    let location = line.location();
+                    (line.value, location)
+                })
+            })
+        })
+    }
+}
+
 impl<R: AsyncRead + Unpin> Stream for Jsonl<R> {
-    type Item = anyhow::Result<String>;
+    type Item = Result<String, JsonlError>;
 
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        match Pin::new(&mut self.lines).poll_next_line(cx) {
-            Poll::Ready(Ok(Some(line))) => {
-                let line = line.trim();
-                if line.is_empty() {
-                    // Skip empty lines and recursively poll for next
-                    self.poll_next(cx)
-                } else {
-                    Poll::Ready(Some(Ok(line.to_string())))
-                }
-            }
-            Poll::Ready(Ok(None)) => Poll::Ready(None), // EOF
-            Poll::Ready(Err(e)) => Poll::Ready(Some(Err(anyhow::anyhow!("IO error: {}", e)))),
-            Poll::Pending => Poll::Pending,
-        }
+        self.poll_next_line(cx)
+            .map(|line| line.map(|result| result.map(|(line, _)| line)))
     }
 }
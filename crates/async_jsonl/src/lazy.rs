@@ -0,0 +1,290 @@
+use crate::{JsonlError, Location};
+use serde::de::{self, DeserializeSeed, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::value::RawValue;
+use std::borrow::Cow;
+use std::fmt;
+
+/// A record kept as raw JSON, parsed only as far as its fields are read.
+///
+/// Fields are addressed by [JSON pointer](https://datatracker.ietf.org/doc/html/rfc6901),
+/// e.g. `/user/id` or `/items/0`. Finding a field skims over the JSON that
+/// leads to it without building values for anything else, so inspecting a tag
+/// and forwarding the untouched line costs little more than validating it.
+///
+/// # Examples
+///
+/// ```ignore
+/// use async_jsonl::{Jsonl, JsonlValueDeserialize};
+/// use futures::StreamExt;
+///
+/// #[tokio::main]
+/// async fn main() -> anyhow::Result<()> {
+///     let mut records = Jsonl::from_path("events.jsonl").await?.deserialize_lazy();
+///
+///     while let Some(record) = records.next().await {
+///         let record = record?;
+///         if record.get_str("/type")?.as_deref() == Some("click") {
+///             let user = record.get_u64("/user/id")?;
+///             println!("{:?}: {}", user, record.get());
+///         }
+///     }
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct LazyRecord {
+    raw: Box<RawValue>,
+    location: Location,
+}
+
+impl LazyRecord {
+    pub(crate) fn new(raw: Box<RawValue>, location: Location) -> Self {
+        Self { raw, location }
+    }
+
+    /// The record's JSON text, without surrounding whitespace.
+    pub fn get(&self) -> &str {
+        self.raw.get()
+    }
+
+    /// The record as a [`RawValue`].
+    pub fn raw(&self) -> &RawValue {
+        &self.raw
+    }
+
+    /// Consume the record, keeping only its raw JSON.
+    pub fn into_raw(self) -> Box<RawValue> {
+        self.raw
+    }
+
+    /// Where the record was found in the input.
+    pub fn location(&self) -> Location {
+        self.location
+    }
+
+    /// Raw JSON of the value at `pointer`, or `None` if there is no such value.
+    pub fn pointer(&self, pointer: &str) -> Result<Option<&RawValue>, JsonlError> {
+        let mut current: &RawValue = &self.raw;
+        let Some(path) = pointer.strip_prefix('/') else {
+            if pointer.is_empty() {
+                return Ok(Some(current));
+            }
+            let source = de::Error::custom("JSON pointer must be empty or start with '/'");
+            return Err(self.field_error(pointer, source));
+        };
+
+        for token in path.split('/') {
+            let token = unescape(token);
+            let mut de = serde_json::Deserializer::from_str(current.get());
+            let next = Step(&token)
+                .deserialize(&mut de)
+                .map_err(|e| self.field_error(pointer, e))?;
+            match next {
+                Some(next) => current = next,
+                None => return Ok(None),
+            }
+        }
+        Ok(Some(current))
+    }
+
+    /// The string at `pointer`, or `None` if there is no such value.
+    ///
+    /// Strings without escape sequences are borrowed from the record.
+    pub fn get_str(&self, pointer: &str) -> Result<Option<Cow<'_, str>>, JsonlError> {
+        self.deserialize_field::<CowStr>(pointer)
+            .map(|value| value.map(|value| value.0))
+    }
+
+    /// The unsigned integer at `pointer`, or `None` if there is no such value.
+    pub fn get_u64(&self, pointer: &str) -> Result<Option<u64>, JsonlError> {
+        self.deserialize_field(pointer)
+    }
+
+    /// Deserialize the value at `pointer`, or return `None` if there is no
+    /// such value. `T` may borrow from the record.
+    pub fn deserialize_field<'a, T>(&'a self, pointer: &str) -> Result<Option<T>, JsonlError>
+    where
+        T: Deserialize<'a>,
+    {
+        match self.pointer(pointer)? {
+            Some(raw) => serde_json::from_str(raw.get())
+                .map(Some)
+                .map_err(|e| self.field_error(pointer, e)),
+            None => Ok(None),
+        }
+    }
+
+    /// Deserialize the whole record.
+    pub fn deserialize<'a, T: Deserialize<'a>>(&'a self) -> Result<T, JsonlError> {
+        serde_json::from_str(self.raw.get()).map_err(|e| self.field_error("", e))
+    }
+
+    fn field_error(&self, pointer: &str, source: serde_json::Error) -> JsonlError {
+        JsonlError::Field {
+            location: self.location,
+            pointer: pointer.to_string(),
+            source,
+        }
+    }
+}
+
+impl Serialize for LazyRecord {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.raw.serialize(serializer)
+    }
+}
+
+/// The value under the top-level `key` of the object `json`, or `None` if
+/// there is no such key. Other fields are skimmed over without building values.
+pub(crate) fn peek<'a>(
+    json: &'a str,
+    key: &str,
+) -> Result<Option<&'a RawValue>, serde_json::Error> {
+    let mut de = serde_json::Deserializer::from_str(json);
+    Step(key).deserialize(&mut de)
+}
+
+/// The string `raw` holds, or `None` if it holds another kind of value.
+pub(crate) fn as_str(raw: &RawValue) -> Option<Cow<'_, str>> {
+    serde_json::from_str::<CowStr>(raw.get())
+        .ok()
+        .map(|value| value.0)
+}
+
+/// Undo the `~1` and `~0` escapes of a JSON pointer token.
+fn unescape(token: &str) -> Cow<'_, str> {
+    if token.contains('~') {
+        Cow::Owned(token.replace("~1", "/").replace("~0", "~"))
+    } else {
+        Cow::Borrowed(token)
+    }
+}
+
+/// Finds one pointer token in an object or array, skipping everything else.
+struct Step<'t>(&'t str);
+
+impl<'de> DeserializeSeed<'de> for Step<'_> {
+    type Value = Option<&'de RawValue>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for Step<'_> {
+    type Value = Option<&'de RawValue>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "an object or array")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut found = None;
+        while let Some(matches) = map.next_key_seed(KeyIs(self.0))? {
+            if matches && found.is_none() {
+                found = Some(map.next_value()?);
+            } else {
+                map.next_value::<IgnoredAny>()?;
+            }
+        }
+        Ok(found)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        // Tokens like "01" or "-" never match an element
+        let index = match self.0.parse::<usize>() {
+            Ok(index) if self.0 == index.to_string() => Some(index),
+            _ => None,
+        };
+        let mut found = None;
+        let mut position = 0;
+        loop {
+            if Some(position) == index {
+                match seq.next_element()? {
+                    Some(value) => found = Some(value),
+                    None => break,
+                }
+            } else if seq.next_element::<IgnoredAny>()?.is_none() {
+                break;
+            }
+            position += 1;
+        }
+        Ok(found)
+    }
+
+    // Scalars have no fields
+    fn visit_bool<E>(self, _: bool) -> Result<Self::Value, E> {
+        Ok(None)
+    }
+
+    fn visit_i64<E>(self, _: i64) -> Result<Self::Value, E> {
+        Ok(None)
+    }
+
+    fn visit_u64<E>(self, _: u64) -> Result<Self::Value, E> {
+        Ok(None)
+    }
+
+    fn visit_f64<E>(self, _: f64) -> Result<Self::Value, E> {
+        Ok(None)
+    }
+
+    fn visit_str<E>(self, _: &str) -> Result<Self::Value, E> {
+        Ok(None)
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E> {
+        Ok(None)
+    }
+}
+
+/// Compares an object key with a pointer token without allocating.
+struct KeyIs<'t>(&'t str);
+
+impl<'de> DeserializeSeed<'de> for KeyIs<'_> {
+    type Value = bool;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<bool, D::Error> {
+        deserializer.deserialize_str(self)
+    }
+}
+
+impl Visitor<'_> for KeyIs<'_> {
+    type Value = bool;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "a string key")
+    }
+
+    fn visit_str<E>(self, key: &str) -> Result<bool, E> {
+        Ok(key == self.0)
+    }
+}
+
+/// String that borrows from the input when it contains no escapes.
+struct CowStr<'a>(Cow<'a, str>);
+
+impl<'de> Deserialize<'de> for CowStr<'de> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct CowStrVisitor;
+
+        impl<'de> Visitor<'de> for CowStrVisitor {
+            type Value = CowStr<'de>;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "a string")
+            }
+
+            fn visit_borrowed_str<E>(self, value: &'de str) -> Result<Self::Value, E> {
+                Ok(CowStr(Cow::Borrowed(value)))
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<Self::Value, E> {
+                Ok(CowStr(Cow::Owned(value.to_string())))
+            }
+        }
+
+        deserializer.deserialize_str(CowStrVisitor)
+    }
+}
@@ -1,6 +1,44 @@
+mod append;
 mod async_jsonl;
+mod atomic;
+mod builder;
+mod bytes;
+mod count;
+mod durable;
+mod encoding;
+mod error;
+mod json_stream;
 mod jsonl_reader;
+mod lazy;
+mod line_reader;
+mod policy;
+mod positioned;
+mod raw;
+mod rev;
+mod rotate;
+mod stream_ext;
+mod tagged;
 mod take_n;
 mod value;
+mod writer;
 
+pub use append::TornTailPolicy;
 pub use async_jsonl::*;
+pub use atomic::AtomicJsonlFile;
+pub use builder::{
+    BlankLinePolicy, Encoding, Framing, JsonlBuilder, LongLinePolicy, TrimPolicy, Utf8Policy,
+};
+pub use bytes::ByteLines;
+pub use count::CountReport;
+pub use durable::{Durability, DurableJsonlWriter};
+pub use error::*;
+pub use json_stream::{JsonStream, PositionedValues};
+pub use lazy::LazyRecord;
+pub use policy::*;
+pub use positioned::*;
+pub use raw::{LineTerminator, RawLine, RawLines};
+pub use rev::RevLines;
+pub use rotate::{Period, RotatingJsonlWriter, Segments};
+pub use stream_ext::*;
+pub use tagged::TaggedStream;
+pub use writer::JsonlWriter;
@@ -0,0 +1,393 @@
+use crate::builder::RECORD_SEPARATOR;
+use crate::encoding::{Decoder, UTF8_BOM};
+use crate::json_stream::is_whitespace;
+use crate::{JsonlError, Location};
+use bytes::{Buf, Bytes, BytesMut};
+use futures::future::poll_fn;
+use std::io;
+use std::ops::Range;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+use tokio::io::{AsyncBufRead, AsyncRead, BufReader, ReadBuf};
+
+/// Where a [`LineReader`] gets its bytes from: a read buffer of its own over
+/// the decoded input, or a reader that is buffered already, used as it is.
+pub(crate) enum Source<R> {
+    Buffered(BufReader<Decoder<R>>),
+    Direct(Direct<R>),
+}
+
+/// A buffered reader, along with its buffer methods.
+pub(crate) struct Direct<R> {
+    reader: R,
+    fill_buf: FillBuf<R>,
+    consume: fn(Pin<&mut R>, usize),
+}
+
+type FillBuf<R> = for<'a> fn(Pin<&'a mut R>, &mut Context<'_>) -> Poll<io::Result<&'a [u8]>>;
+
+impl<R> Source<R> {
+    /// Read lines straight out of the buffer of `reader`, without copying
+    /// them into another one.
+    pub(crate) fn direct(reader: R) -> Self
+    where
+        R: AsyncBufRead,
+    {
+        // Picked here, where `R` is known to be buffered
+        Source::Direct(Direct {
+            reader,
+            fill_buf: R::poll_fill_buf,
+            consume: R::consume,
+        })
+    }
+
+    /// The underlying source. Bytes read ahead into a buffer of our own are lost.
+    pub(crate) fn into_inner(self) -> R
+    where
+        R: AsyncRead + Unpin,
+    {
+        match self {
+            Source::Buffered(reader) => reader.into_inner().into_inner(),
+            Source::Direct(direct) => direct.reader,
+        }
+    }
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for Source<R> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Source::Buffered(reader) => Pin::new(reader).poll_read(cx, buf),
+            Source::Direct(direct) => Pin::new(&mut direct.reader).poll_read(cx, buf),
+        }
+    }
+}
+
+impl<R: AsyncRead + Unpin> AsyncBufRead for Source<R> {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        match self.get_mut() {
+            Source::Buffered(reader) => Pin::new(reader).poll_fill_buf(cx),
+            Source::Direct(direct) => (direct.fill_buf)(Pin::new(&mut direct.reader), cx),
+        }
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        match self.get_mut() {
+            Source::Buffered(reader) => Pin::new(reader).consume(amt),
+            Source::Direct(direct) => (direct.consume)(Pin::new(&mut direct.reader), amt),
+        }
+    }
+}
+
+/// Splits a buffered reader into lines while keeping track of where each line starts.
+pub(crate) struct LineReader<B> {
+    reader: B,
+    /// Raw bytes of the current line, including its terminator.
+    ///
+    /// With a length limit only the start of an overlong line is kept.
+    buf: BytesMut,
+    /// Raw length of the current line, including bytes that were not kept.
+    len: u64,
+    /// Whether bytes of the current line were dropped because of the length limit.
+    dropped: bool,
+    /// Maximum line length in bytes, excluding the terminator.
+    max_len: Option<usize>,
+    /// Whether `buf` holds a line that was already handed out.
+    complete: bool,
+    /// Number of lines read so far.
+    line: u64,
+    /// Byte offset of the first byte of the next line.
+    offset: u64,
+    /// Number of the last line to read, if reading stops before EOF.
+    end: Option<u64>,
+    /// Whether a byte order mark is dropped from the first line.
+    strip_bom: bool,
+    /// Whether lines are the records of a JSON text sequence, split at record
+    /// separators rather than newlines.
+    json_seq: bool,
+    /// Last byte of the current line before its terminator.
+    last: u8,
+}
+
+impl<B: AsyncBufRead + Unpin> LineReader<B> {
+    pub(crate) fn new(reader: B, max_len: Option<usize>) -> Self {
+        Self {
+            reader,
+            buf: BytesMut::new(),
+            len: 0,
+            dropped: false,
+            max_len,
+            complete: false,
+            line: 0,
+            offset: 0,
+            end: None,
+            strip_bom: true,
+            json_seq: false,
+            last: 0,
+        }
+    }
+
+    pub(crate) fn into_inner(self) -> B {
+        self.reader
+    }
+
+    /// The underlying reader, positioned after the last line read.
+    pub(crate) fn get_mut(&mut self) -> &mut B {
+        &mut self.reader
+    }
+
+    /// Read the next line, returning its location.
+    ///
+    /// The content of the line is available through [`LineReader::line`] until the next call.
+    pub(crate) fn poll_next_line(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<io::Result<Option<Location>>> {
+        self.reset();
+        if self.at_end() {
+            return Poll::Ready(Ok(None));
+        }
+
+        let delimiter = self.delimiter();
+        loop {
+            let available = ready!(Pin::new(&mut self.reader).poll_fill_buf(cx))?;
+            if available.is_empty() {
+                // EOF: a trailing line without a newline is still a line
+                if self.len == 0 {
+                    return Poll::Ready(Ok(None));
+                }
+                return Poll::Ready(Ok(Some(self.finish_line())));
+            }
+
+            let newline = memchr::memchr(delimiter, available);
+            let used = newline.map_or(available.len(), |newline| newline + 1);
+            if let Some(&last) = available[..newline.unwrap_or(used)].last() {
+                self.last = last;
+            }
+            self.len += used as u64;
+            self.dropped |= append_bounded(&mut self.buf, &available[..used], self.max_len);
+            Pin::new(&mut self.reader).consume(used);
+
+            if newline.is_some() {
+                if self.is_bare_separator() {
+                    // Empty records of a sequence are ignored, not counted
+                    self.offset += self.len;
+                    self.buf.clear();
+                    self.len = 0;
+                    self.last = 0;
+                    continue;
+                }
+                return Poll::Ready(Ok(Some(self.finish_line())));
+            }
+        }
+    }
+
+    /// Skip up to `n` lines without keeping their content, returning how many were skipped.
+    ///
+    /// Lines are found with a newline scan over the read buffer, so nothing
+    /// is copied or validated. Fewer lines are skipped when EOF or the limit
+    /// set by [`LineReader::limit`] comes first.
+    pub(crate) async fn skip(&mut self, n: u64) -> io::Result<u64> {
+        let mut remaining = n;
+        poll_fn(|cx| self.poll_skip(cx, &mut remaining)).await?;
+        Ok(n - remaining)
+    }
+
+    fn poll_skip(&mut self, cx: &mut Context<'_>, remaining: &mut u64) -> Poll<io::Result<()>> {
+        // A line partly read before is skipped as well, so only its length is kept
+        self.reset();
+        self.buf.clear();
+        self.dropped = false;
+        self.last = 0;
+        let delimiter = self.delimiter();
+        let end = self.end.unwrap_or(u64::MAX);
+        while *remaining > 0 && self.line < end {
+            let available = ready!(Pin::new(&mut self.reader).poll_fill_buf(cx))?;
+            if available.is_empty() {
+                if self.len > 0 {
+                    self.line += 1;
+                    self.offset += self.len;
+                    self.len = 0;
+                    *remaining -= 1;
+                }
+                break;
+            }
+
+            let mut used = 0;
+            for newline in memchr::memchr_iter(delimiter, available) {
+                let len = self.len + (newline + 1 - used) as u64;
+                self.offset += len;
+                self.len = 0;
+                used = newline + 1;
+                if self.json_seq && len == 1 {
+                    continue;
+                }
+                self.line += 1;
+                *remaining -= 1;
+                if *remaining == 0 || self.line >= end {
+                    break;
+                }
+            }
+            if *remaining > 0 && self.line < end {
+                // The rest belongs to a line that goes on in the next chunk
+                self.len += (available.len() - used) as u64;
+                used = available.len();
+            }
+            Pin::new(&mut self.reader).consume(used);
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    /// Stop reading once `lines` more lines have been read.
+    pub(crate) fn limit(&mut self, lines: u64) {
+        let end = self.line.saturating_add(lines);
+        self.end = Some(self.end.map_or(end, |current| current.min(end)));
+    }
+
+    /// Split lines at the record separators of a JSON text sequence.
+    pub(crate) fn set_json_seq(&mut self, json_seq: bool) {
+        self.json_seq = json_seq;
+    }
+
+    /// Whether lines are the records of a JSON text sequence.
+    pub(crate) fn is_json_seq(&self) -> bool {
+        self.json_seq
+    }
+
+    /// Whether the last record read is part of a JSON text sequence and was
+    /// cut short, telling by the lack of whitespace at its end.
+    pub(crate) fn truncated(&self) -> bool {
+        self.json_seq && !is_whitespace(self.last)
+    }
+
+    fn delimiter(&self) -> u8 {
+        match self.json_seq {
+            true => RECORD_SEPARATOR,
+            false => b'\n',
+        }
+    }
+
+    /// Whether the line just read is nothing but a record separator.
+    fn is_bare_separator(&self) -> bool {
+        if !self.json_seq || self.dropped {
+            return false;
+        }
+        let content = &self.buf[..self.buf.len() - 1];
+        content.is_empty() || (self.line == 0 && self.strip_bom && content == UTF8_BOM)
+    }
+
+    /// Whether the last line to read, if any, was read.
+    fn at_end(&self) -> bool {
+        self.end.is_some_and(|end| self.line >= end)
+    }
+
+    /// Whether reading stops at a line number rather than at EOF.
+    pub(crate) fn is_limited(&self) -> bool {
+        self.end.is_some()
+    }
+
+    /// Forget the line handed out last, making room for the next one.
+    fn reset(&mut self) {
+        if self.complete {
+            self.buf.clear();
+            self.len = 0;
+            self.dropped = false;
+            self.complete = false;
+            self.last = 0;
+        }
+    }
+
+    fn finish_line(&mut self) -> Location {
+        self.line += 1;
+        if self.line == 1 && self.strip_bom && self.buf.starts_with(UTF8_BOM) {
+            self.buf.advance(UTF8_BOM.len());
+        }
+        let location = Location::new(self.line, self.offset);
+        self.offset += self.len;
+        self.complete = true;
+        location
+    }
+
+    /// Whether bytes of the last line read were dropped for exceeding the length limit.
+    pub(crate) fn overflowed(&self) -> bool {
+        self.dropped
+    }
+
+    /// Keep a byte order mark at the start of the first line.
+    pub(crate) fn keep_bom(&mut self) {
+        self.strip_bom = false;
+    }
+
+    /// Split the last line read off the buffer, terminator included, without copying.
+    pub(crate) fn take_raw(&mut self) -> Bytes {
+        self.buf.split().freeze()
+    }
+
+    /// Split `range` of the last line read off the buffer, without copying.
+    ///
+    /// The line is no longer available through [`LineReader::line`] afterwards.
+    pub(crate) fn take(&mut self, range: Range<usize>) -> Bytes {
+        self.buf.split_to(range.end).freeze().slice(range.start..)
+    }
+
+    /// Raw length of the last line read, including its terminator.
+    pub(crate) fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// 1-based number of the last line read.
+    pub(crate) fn line_number(&self) -> u64 {
+        self.line
+    }
+
+    /// Byte offset of the start of the last line read.
+    pub(crate) fn line_offset(&self) -> u64 {
+        self.offset - self.len
+    }
+
+    /// Content of the last line read, without its `\n` or `\r\n` terminator.
+    ///
+    /// Records of a JSON text sequence lose the separator that follows them too.
+    pub(crate) fn line(&self) -> &[u8] {
+        let mut line = &self.buf[..];
+        if self.json_seq {
+            line = line.strip_suffix(&[RECORD_SEPARATOR]).unwrap_or(line);
+        }
+        let line = line.strip_suffix(b"\n").unwrap_or(line);
+        line.strip_suffix(b"\r").unwrap_or(line)
+    }
+}
+
+/// Append `bytes` to `buf`, keeping at most `max_len` bytes plus a `\r\n` terminator.
+///
+/// Returns whether any byte was dropped.
+pub(crate) fn append_bounded(buf: &mut BytesMut, bytes: &[u8], max_len: Option<usize>) -> bool {
+    let room = match max_len {
+        Some(limit) => (limit + 2).saturating_sub(buf.len()),
+        None => usize::MAX,
+    };
+    let kept = room.min(bytes.len());
+    buf.extend_from_slice(&bytes[..kept]);
+    kept < bytes.len()
+}
+
+/// Cut an overlong line down to `limit` bytes without splitting a UTF-8 character.
+pub(crate) fn truncate_line(line: &[u8], limit: usize) -> &[u8] {
+    let line = &line[..line.len().min(limit)];
+    match std::str::from_utf8(line) {
+        // Only an incomplete character at the very end is dropped
+        Err(e) if e.error_len().is_none() => &line[..e.valid_up_to()],
+        _ => line,
+    }
+}
+
+/// A stream of non-empty lines that knows where each line came from.
+pub(crate) trait LineStream: Unpin {
+    fn poll_next_line(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<(String, Location), JsonlError>>>;
+}
@@ -0,0 +1,181 @@
+use crate::line_reader::LineStream;
+use crate::JsonlError;
+use futures::{Sink, SinkExt, Stream};
+use serde::Deserialize;
+use std::fmt;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+
+/// A line that failed, as handed to an [`ErrorPolicy::DeadLetter`] sink.
+#[derive(Debug)]
+pub struct DeadLetter {
+    /// The line as read, or `None` when the reader rejected it before it could
+    /// be handed out (invalid UTF-8, too long, unexpected blank line).
+    pub line: Option<String>,
+    /// Why the line failed.
+    pub error: JsonlError,
+}
+
+/// Sink receiving the lines that failed under [`ErrorPolicy::DeadLetter`].
+pub type DeadLetterSink = Pin<Box<dyn Sink<DeadLetter, Error = JsonlError> + Send>>;
+
+/// What [`JsonlDeserialize::deserialize_with`](crate::JsonlDeserialize::deserialize_with)
+/// does with lines that cannot be read or deserialized.
+///
+/// Policies only apply to bad records. IO errors always end the stream,
+/// since the reader cannot make progress past them.
+#[derive(Default)]
+pub enum ErrorPolicy {
+    /// Yield the first error, then end the stream.
+    #[default]
+    FailFast,
+    /// Drop failing lines, counting them in [`DeserializeStream::skipped`].
+    Skip,
+    /// Drop failing lines and yield a single [`JsonlError::Collected`] once
+    /// the input is exhausted.
+    Collect,
+    /// Send failing lines to a sink and carry on.
+    DeadLetter(DeadLetterSink),
+}
+
+impl ErrorPolicy {
+    /// Send failing lines to `sink`, e.g. the sending half of a channel.
+    ///
+    /// Errors of the sink end the stream with a [`JsonlError::DeadLetter`].
+    pub fn dead_letter<Si>(sink: Si) -> Self
+    where
+        Si: Sink<DeadLetter> + Send + 'static,
+        Si::Error: std::error::Error + Send + Sync + 'static,
+    {
+        Self::DeadLetter(Box::pin(
+            sink.sink_map_err(|e| JsonlError::DeadLetter(Box::new(e))),
+        ))
+    }
+}
+
+impl fmt::Debug for ErrorPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::FailFast => write!(f, "FailFast"),
+            Self::Skip => write!(f, "Skip"),
+            Self::Collect => write!(f, "Collect"),
+            Self::DeadLetter(_) => write!(f, "DeadLetter(..)"),
+        }
+    }
+}
+
+/// Stream of deserialized records governed by an [`ErrorPolicy`].
+///
+/// Created by [`JsonlDeserialize::deserialize_with`](crate::JsonlDeserialize::deserialize_with).
+pub struct DeserializeStream<S, T> {
+    lines: S,
+    policy: ErrorPolicy,
+    skipped: u64,
+    collected: Vec<JsonlError>,
+    /// Failed line waiting for the dead letter sink to accept it
+    pending: Option<DeadLetter>,
+    eof: bool,
+    done: bool,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<S, T> DeserializeStream<S, T> {
+    pub(crate) fn new(lines: S, policy: ErrorPolicy) -> Self {
+        Self {
+            lines,
+            policy,
+            skipped: 0,
+            collected: Vec::new(),
+            pending: None,
+            eof: false,
+            done: false,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Number of failing lines dropped so far.
+    pub fn skipped(&self) -> u64 {
+        self.skipped
+    }
+
+    fn fail(&mut self, error: JsonlError) -> Poll<Option<Result<T, JsonlError>>> {
+        self.done = true;
+        Poll::Ready(Some(Err(error)))
+    }
+}
+
+impl<S, T> Stream for DeserializeStream<S, T>
+where
+    S: LineStream,
+    T: for<'a> Deserialize<'a>,
+{
+    type Item = Result<T, JsonlError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        loop {
+            if this.done {
+                return Poll::Ready(None);
+            }
+
+            if let ErrorPolicy::DeadLetter(sink) = &mut this.policy {
+                if this.pending.is_some() {
+                    // The letter stays pending until the sink has room for it
+                    if let Err(e) = ready!(sink.as_mut().poll_ready(cx)) {
+                        return this.fail(e);
+                    }
+                    let letter = this.pending.take().expect("letter is pending");
+                    if let Err(e) = sink.as_mut().start_send(letter) {
+                        return this.fail(e);
+                    }
+                }
+                if this.eof {
+                    if let Err(e) = ready!(sink.as_mut().poll_flush(cx)) {
+                        return this.fail(e);
+                    }
+                }
+            }
+
+            if this.eof {
+                this.done = true;
+                if this.collected.is_empty() {
+                    return Poll::Ready(None);
+                }
+                let errors = std::mem::take(&mut this.collected);
+                return Poll::Ready(Some(Err(JsonlError::Collected(errors))));
+            }
+
+            let (line, error) = match ready!(this.lines.poll_next_line(cx)) {
+                Some(Ok((line, location))) => match serde_json::from_str::<T>(&line) {
+                    Ok(value) => return Poll::Ready(Some(Ok(value))),
+                    Err(e) => {
+                        let error = JsonlError::parse(location, line.as_bytes(), e);
+                        (Some(line), error)
+                    }
+                },
+                Some(Err(error)) => (None, error),
+                None => {
+                    this.eof = true;
+                    continue;
+                }
+            };
+
+            if matches!(error, JsonlError::Io(_)) {
+                return this.fail(error);
+            }
+            match this.policy {
+                ErrorPolicy::FailFast => return this.fail(error),
+                ErrorPolicy::Skip => this.skipped += 1,
+                ErrorPolicy::Collect => {
+                    this.skipped += 1;
+                    this.collected.push(error);
+                }
+                ErrorPolicy::DeadLetter(_) => {
+                    this.skipped += 1;
+                    this.pending = Some(DeadLetter { line, error });
+                }
+            }
+        }
+    }
+}
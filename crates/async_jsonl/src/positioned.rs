@@ -0,0 +1,62 @@
+use crate::{Jsonl, JsonlError, Location};
+use futures::Stream;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::io::AsyncRead;
+
+/// A record together with where it was found in the input.
+///
+/// Blank lines skipped by the reader still count towards `line` and `offset`,
+/// so both always point at the exact spot in the source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Positioned<T> {
+    /// 1-based line number
+    pub line: u64,
+    /// Byte offset of the first byte of the line
+    pub offset: u64,
+    /// Raw length of the line in bytes, including its terminator
+    pub len: u64,
+    /// The record itself
+    pub value: T,
+}
+
+impl<T> Positioned<T> {
+    /// Byte offset just past this record, where reading can resume.
+    pub fn end(&self) -> u64 {
+        self.offset + self.len
+    }
+
+    /// Location of the record, as reported by [`JsonlError`].
+    pub fn location(&self) -> Location {
+        Location::new(self.line, self.offset)
+    }
+
+    /// Transform the record while keeping its position.
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Positioned<U> {
+        Positioned {
+            line: self.line,
+            offset: self.offset,
+            len: self.len,
+            value: f(self.value),
+        }
+    }
+}
+
+/// Stream of lines with their positions, created by [`Jsonl::with_positions`].
+pub struct PositionedLines<R> {
+    inner: Jsonl<R>,
+}
+
+impl<R> PositionedLines<R> {
+    pub(crate) fn new(inner: Jsonl<R>) -> Self {
+        Self { inner }
+    }
+}
+
+impl<R: AsyncRead + Unpin> Stream for PositionedLines<R> {
+    type Item = Result<Positioned<String>, JsonlError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.poll_next_positioned(cx)
+    }
+}
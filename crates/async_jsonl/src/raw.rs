@@ -0,0 +1,93 @@
+use crate::{Jsonl, JsonlError, Positioned};
+use bytes::Bytes;
+use futures::Stream;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+use tokio::io::AsyncRead;
+
+/// How a line ended in the input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LineTerminator {
+    /// `\n`
+    Lf,
+    /// `\r\n`
+    CrLf,
+    /// Nothing: the last line of an input without a trailing newline.
+    None,
+}
+
+impl LineTerminator {
+    /// The terminator as it appeared in the input.
+    pub fn as_bytes(self) -> &'static [u8] {
+        match self {
+            LineTerminator::Lf => b"\n",
+            LineTerminator::CrLf => b"\r\n",
+            LineTerminator::None => b"",
+        }
+    }
+}
+
+/// A line exactly as stored in the input, yielded by [`Jsonl::raw_lines`].
+///
+/// Writing `content` followed by `terminator` for every line reproduces the
+/// input byte for byte.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawLine {
+    /// Bytes of the line, without its terminator.
+    pub content: Bytes,
+    /// The terminator that ended the line.
+    pub terminator: LineTerminator,
+}
+
+/// Stream of lines exactly as stored, created by [`Jsonl::raw_lines`].
+pub struct RawLines<R> {
+    inner: Jsonl<R>,
+}
+
+impl<R: AsyncRead + Unpin> RawLines<R> {
+    pub(crate) fn new(mut inner: Jsonl<R>) -> Self {
+        inner.lines.keep_bom();
+        inner.lines.set_json_seq(false);
+        Self { inner }
+    }
+}
+
+impl<R: AsyncRead + Unpin> Stream for RawLines<R> {
+    type Item = Result<Positioned<RawLine>, JsonlError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let Jsonl { lines, options, .. } = &mut self.inner;
+        let location = match ready!(lines.poll_next_line(cx)) {
+            Ok(Some(location)) => location,
+            Ok(None) => return Poll::Ready(None), // EOF
+            Err(e) => return Poll::Ready(Some(Err(e.into()))),
+        };
+        if let Some(limit) = options.max_line_length {
+            // A cut line can't be handed out as stored, whatever the policy
+            if lines.overflowed() || lines.line().len() > limit {
+                let e = JsonlError::line_too_long(location, limit, lines.line());
+                return Poll::Ready(Some(Err(e)));
+            }
+        }
+
+        let (line, offset, len) = (lines.line_number(), lines.line_offset(), lines.len());
+        let raw = lines.take_raw();
+        let terminator = if raw.ends_with(b"\r\n") {
+            LineTerminator::CrLf
+        } else if raw.ends_with(b"\n") {
+            LineTerminator::Lf
+        } else {
+            LineTerminator::None
+        };
+        let content = raw.slice(..raw.len() - terminator.as_bytes().len());
+        Poll::Ready(Some(Ok(Positioned {
+            line,
+            offset,
+            len,
+            value: RawLine {
+                content,
+                terminator,
+            },
+        })))
+    }
+}
@@ -0,0 +1,231 @@
+use crate::builder::{Framing, LineAction, ReadOptions, RECORD_SEPARATOR};
+use crate::encoding::{decode_utf16_line, UTF8_BOM};
+use crate::json_stream::is_whitespace;
+use crate::line_reader::LineStream;
+use crate::{Encoding, JsonlError, Location};
+use async_rev_buf::{Newline, RevBufReader};
+use futures::Stream;
+use std::io::{self, SeekFrom};
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+use tokio::io::{AsyncRead, AsyncSeek, ReadBuf};
+
+/// Stream of lines from the end of a JSONL source back to its start, created
+/// by [`Jsonl::rev`](crate::Jsonl::rev).
+///
+/// Lines are read lazily in chunks of the buffer capacity, so memory stays
+/// constant however far back the stream is followed, as long as lines are
+/// bounded with [`JsonlBuilder::max_line_length`](crate::JsonlBuilder::max_line_length).
+/// Lines go through the same trimming, comment, blank line and UTF-8
+/// handling as with [`Jsonl`](crate::Jsonl). Errors carry the byte offset of
+/// the line; line numbers are unknown without reading from the start.
+pub struct RevLines<R> {
+    reader: RevBufReader<R>,
+    options: ReadOptions,
+    line: Vec<u8>,
+    /// The current line transcoded to UTF-8, for UTF-16 input
+    decoded: Vec<u8>,
+    /// Progress of looking for a byte order mark, with [`Encoding::Auto`]
+    detect: Option<Detect>,
+    /// Whether the stream ended on an unsupported combination of settings
+    failed: bool,
+    /// Whether the first record of the source is a header to leave out
+    has_header: bool,
+    /// The record read last, held back until an earlier record shows it is
+    /// not the header
+    held: Option<Result<(String, Location), JsonlError>>,
+}
+
+enum Detect {
+    Seek,
+    Seeking,
+    Read { head: [u8; 2], filled: usize },
+}
+
+impl<R: AsyncRead + AsyncSeek + Unpin> RevLines<R> {
+    pub(crate) fn new(reader: R, options: ReadOptions, has_header: bool) -> Self {
+        let mut reader = RevBufReader::with_capacity(options.buffer_capacity, reader);
+        let detect = match options.encoding {
+            Encoding::Auto => Some(Detect::Seek),
+            encoding => {
+                reader.set_newline(newline(encoding, options.framing));
+                None
+            }
+        };
+        Self {
+            reader,
+            options,
+            line: Vec::new(),
+            decoded: Vec::new(),
+            detect,
+            failed: false,
+            has_header,
+            held: None,
+        }
+    }
+
+    /// Consume the stream, returning the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.reader.into_inner()
+    }
+
+    /// Read the first bytes of the source to tell its encoding.
+    fn poll_detect(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        loop {
+            let Some(detect) = &mut self.detect else {
+                return Poll::Ready(Ok(()));
+            };
+            let source = Pin::new(self.reader.get_mut());
+            match detect {
+                Detect::Seek => {
+                    source.start_seek(SeekFrom::Start(0))?;
+                    *detect = Detect::Seeking;
+                }
+                Detect::Seeking => {
+                    ready!(source.poll_complete(cx))?;
+                    *detect = Detect::Read {
+                        head: [0; 2],
+                        filled: 0,
+                    };
+                }
+                Detect::Read { head, filled } => {
+                    if *filled < head.len() {
+                        let mut buf = ReadBuf::new(&mut head[*filled..]);
+                        ready!(source.poll_read(cx, &mut buf))?;
+                        let read = buf.filled().len();
+                        if read > 0 {
+                            *filled += read;
+                            continue;
+                        }
+                    }
+                    let encoding = match &head[..*filled] {
+                        [0xFF, 0xFE] => Encoding::Utf16Le,
+                        [0xFE, 0xFF] => Encoding::Utf16Be,
+                        _ => Encoding::Utf8,
+                    };
+                    self.options.encoding = encoding;
+                    self.reader
+                        .set_newline(newline(encoding, self.options.framing));
+                    self.detect = None;
+                }
+            }
+        }
+    }
+}
+
+fn newline(encoding: Encoding, framing: Framing) -> Newline {
+    match (encoding, framing) {
+        (Encoding::Utf8 | Encoding::Auto, Framing::Lines) => Newline::Byte,
+        (Encoding::Utf8 | Encoding::Auto, Framing::JsonSeq) => Newline::Custom(RECORD_SEPARATOR),
+        (Encoding::Utf16Le, _) => Newline::Utf16Le,
+        (Encoding::Utf16Be, _) => Newline::Utf16Be,
+    }
+}
+
+impl<R: AsyncRead + AsyncSeek + Unpin> LineStream for RevLines<R> {
+    fn poll_next_line(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<(String, Location), JsonlError>>> {
+        if !self.has_header {
+            return self.poll_record(cx);
+        }
+        loop {
+            match ready!(self.poll_record(cx)) {
+                Some(Err(JsonlError::Io(e))) => return Poll::Ready(Some(Err(e.into()))),
+                Some(record) => {
+                    if let Some(later) = self.held.replace(record) {
+                        return Poll::Ready(Some(later));
+                    }
+                }
+                // The record held back is the first one: the header
+                None => {
+                    self.held = None;
+                    return Poll::Ready(None);
+                }
+            }
+        }
+    }
+}
+
+impl<R: AsyncRead + AsyncSeek + Unpin> RevLines<R> {
+    /// Read the previous record, header included.
+    fn poll_record(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<(String, Location), JsonlError>>> {
+        if let Err(e) = ready!(self.poll_detect(cx)) {
+            return Poll::Ready(Some(Err(e.into())));
+        }
+
+        let utf16 = match self.options.encoding {
+            Encoding::Utf16Le => Some(false),
+            Encoding::Utf16Be => Some(true),
+            Encoding::Utf8 | Encoding::Auto => None,
+        };
+        let json_seq = self.options.framing == Framing::JsonSeq;
+        if json_seq && utf16.is_some() {
+            if self.failed {
+                return Poll::Ready(None);
+            }
+            self.failed = true;
+            let e = io::Error::new(
+                io::ErrorKind::Unsupported,
+                "JSON text sequences can only be read backwards from UTF-8 input",
+            );
+            return Poll::Ready(Some(Err(e.into())));
+        }
+        // One character past the limit is enough to tell an overlong line
+        // apart, and takes at most two bytes of UTF-16 per byte of UTF-8
+        let limit = match self.options.max_line_length {
+            Some(limit) if utf16.is_some() => limit.saturating_add(1).saturating_mul(2),
+            Some(limit) => limit.saturating_add(1),
+            None => usize::MAX,
+        };
+        loop {
+            let range = match ready!(self.reader.poll_read_line_raw(cx, &mut self.line, limit)) {
+                Ok(Some(range)) => range,
+                Ok(None) => return Poll::Ready(None),
+                Err(e) => return Poll::Ready(Some(Err(e.into()))),
+            };
+            let location = Location::at_offset(range.start);
+            let overflowed = range.end - range.start > self.line.len() as u64;
+
+            let mut line = self.line.as_slice();
+            if let Some(big_endian) = utf16 {
+                decode_utf16_line(line, big_endian, &mut self.decoded);
+                line = &self.decoded;
+            }
+            if range.start == 0 {
+                line = line.strip_prefix(UTF8_BOM).unwrap_or(line);
+            }
+            if json_seq {
+                if line.is_empty() {
+                    continue; // Empty records of a sequence are ignored
+                }
+                if !overflowed && !line.last().copied().is_some_and(is_whitespace) {
+                    let e = JsonlError::truncated(location, line);
+                    return Poll::Ready(Some(Err(e)));
+                }
+                line = line.strip_suffix(b"\n").unwrap_or(line);
+            }
+            let line = line.strip_suffix(b"\r").unwrap_or(line);
+            let line = match self.options.process(line, location, overflowed) {
+                LineAction::Borrowed(line) => line.to_string(),
+                LineAction::Owned(line) => line,
+                LineAction::Skip => continue,
+                LineAction::Reject(e) => return Poll::Ready(Some(Err(e))),
+            };
+            return Poll::Ready(Some(Ok((line, location))));
+        }
+    }
+}
+
+impl<R: AsyncRead + AsyncSeek + Unpin> Stream for RevLines<R> {
+    type Item = Result<String, JsonlError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.poll_next_line(cx)
+            .map(|line| line.map(|result| result.map(|(line, _)| line)))
+    }
+}
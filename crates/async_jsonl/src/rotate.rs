@@ -0,0 +1,563 @@
+use crate::{Framing, Jsonl, JsonlError};
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::fs::{File, OpenOptions};
+use tokio::io::{AsyncRead, AsyncWriteExt, BufWriter, ReadBuf};
+
+const SECONDS_PER_DAY: u64 = 86_400;
+
+/// Time boundaries at which a [`RotatingJsonlWriter`] starts a new segment.
+///
+/// Periods are aligned to UTC, and segments are named after the start of
+/// their period, e.g. `events.2026-10-16T10.jsonl` for an hourly segment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Period {
+    /// Named like `events.2026-10-16T10-30.jsonl`.
+    Minute,
+    /// Named like `events.2026-10-16T10.jsonl`.
+    Hour,
+    /// Named like `events.2026-10-16.jsonl`.
+    Day,
+}
+
+impl Period {
+    fn seconds(self) -> u64 {
+        match self {
+            Period::Minute => 60,
+            Period::Hour => 3_600,
+            Period::Day => SECONDS_PER_DAY,
+        }
+    }
+
+    /// Name of the period containing `secs` seconds since the epoch.
+    fn stamp(self, secs: u64) -> String {
+        let (year, month, day) = civil_from_days(secs / SECONDS_PER_DAY);
+        let hour = secs % SECONDS_PER_DAY / 3_600;
+        let minute = secs % 3_600 / 60;
+        match self {
+            Period::Minute => format!("{year:04}-{month:02}-{day:02}T{hour:02}-{minute:02}"),
+            Period::Hour => format!("{year:04}-{month:02}-{day:02}T{hour:02}"),
+            Period::Day => format!("{year:04}-{month:02}-{day:02}"),
+        }
+    }
+}
+
+/// Gregorian date of the day `days` after 1970-01-01.
+fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    // Counts from 0000-03-01, so leap days end the 400-year eras and years
+    let days = days + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_from_march = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_from_march + 2) / 5 + 1;
+    let month = if month_from_march < 10 {
+        month_from_march + 3
+    } else {
+        month_from_march - 9
+    };
+    let year = era * 400 + year_of_era + u64::from(month <= 2);
+    (year, month, day)
+}
+
+fn epoch_seconds(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// Position of a segment among the segments of one base path.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct SegmentName {
+    stamp: String,
+    /// Tells apart segments started in the same period, from 0
+    index: u64,
+}
+
+/// A segment file found on disk.
+struct SegmentFile {
+    name: SegmentName,
+    path: PathBuf,
+    compressed: bool,
+}
+
+/// How segment files of a base path such as `logs/events.jsonl` are named:
+/// `logs/events.<stamp>[.<index>].jsonl[.gz]`.
+struct Naming {
+    dir: PathBuf,
+    stem: String,
+    extension: Option<String>,
+}
+
+impl Naming {
+    fn new(path: &Path) -> io::Result<Self> {
+        let stem = path.file_stem().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{} does not name a file", path.display()),
+            )
+        })?;
+        let dir = match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+        Ok(Self {
+            dir,
+            stem: stem.to_string_lossy().into_owned(),
+            extension: path
+                .extension()
+                .map(|extension| extension.to_string_lossy().into_owned()),
+        })
+    }
+
+    fn path(&self, name: &SegmentName) -> PathBuf {
+        let mut file_name = format!("{}.{}", self.stem, name.stamp);
+        if name.index > 0 {
+            file_name.push_str(&format!(".{}", name.index));
+        }
+        if let Some(extension) = &self.extension {
+            file_name.push_str(&format!(".{}", extension));
+        }
+        self.dir.join(file_name)
+    }
+
+    fn parse(&self, file_name: &str) -> Option<(SegmentName, bool)> {
+        let rest = file_name.strip_prefix(&self.stem)?.strip_prefix('.')?;
+        let (rest, compressed) = match rest.strip_suffix(".gz") {
+            Some(rest) => (rest, true),
+            None => (rest, false),
+        };
+        let rest = match &self.extension {
+            Some(extension) => rest.strip_suffix(extension.as_str())?.strip_suffix('.')?,
+            None => rest,
+        };
+        let (stamp, index) = match rest.split_once('.') {
+            Some((stamp, index)) => (stamp, index.parse().ok().filter(|&index| index > 0)?),
+            None => (rest, 0),
+        };
+        let is_stamp = stamp.starts_with(|c: char| c.is_ascii_digit())
+            && stamp
+                .chars()
+                .all(|c| c.is_ascii_digit() || c == '-' || c == 'T');
+        is_stamp.then(|| {
+            let name = SegmentName {
+                stamp: stamp.to_string(),
+                index,
+            };
+            (name, compressed)
+        })
+    }
+
+    /// Every segment on disk, oldest first.
+    async fn list(&self) -> io::Result<Vec<SegmentFile>> {
+        let mut segments = Vec::new();
+        let mut entries = tokio::fs::read_dir(&self.dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let file_name = entry.file_name();
+            if let Some((name, compressed)) = file_name.to_str().and_then(|n| self.parse(n)) {
+                segments.push(SegmentFile {
+                    name,
+                    path: entry.path(),
+                    compressed,
+                });
+            }
+        }
+        segments.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(segments)
+    }
+}
+
+/// The segment being written.
+struct Segment {
+    file: BufWriter<File>,
+    path: PathBuf,
+    size: u64,
+    records: u64,
+    /// Number of the period it belongs to, counted from the epoch
+    period: u64,
+}
+
+/// Writes JSON lines into a series of segment files, starting a new one
+/// when the current one reaches a size, a number of records or the end of a
+/// time period.
+///
+/// Segments of a base path such as `logs/events.jsonl` are named
+/// `logs/events.<stamp>.jsonl`, after the start of their [`Period`] (hourly
+/// when only rotating by size or records), with `.1`, `.2`, … before the
+/// extension when several start in the same period. A new segment is
+/// started on every run, so no segment is ever appended to by two writers.
+/// [`Jsonl::from_segments`] reads them all back as one stream.
+///
+/// Closed segments can be gzip-compressed (with the `gzip` feature) and are
+/// deleted once there are more than a given number of them or they are
+/// older than a given age. Both happen when the segment is closed, in the
+/// write that starts the next one.
+///
+/// # Examples
+///
+/// ```ignore
+/// use async_jsonl::{Period, RotatingJsonlWriter};
+/// use serde_json::json;
+/// use std::time::Duration;
+///
+/// #[tokio::main]
+/// async fn main() -> anyhow::Result<()> {
+///     let mut writer = RotatingJsonlWriter::new("logs/events.jsonl")?
+///         .period(Period::Hour)
+///         .max_size(64 * 1024 * 1024)
+///         .max_age(Duration::from_secs(7 * 24 * 3_600));
+///
+///     writer.write(&json!({"event": "started"})).await?;
+///     writer.finish().await?;
+///     Ok(())
+/// }
+/// ```
+pub struct RotatingJsonlWriter {
+    naming: Naming,
+    max_size: Option<u64>,
+    max_records: Option<u64>,
+    period: Option<Period>,
+    compress: bool,
+    keep_segments: Option<usize>,
+    max_age: Option<Duration>,
+    clock: fn() -> SystemTime,
+    current: Option<Segment>,
+    /// Serialized form of the record being written
+    buf: Vec<u8>,
+}
+
+impl RotatingJsonlWriter {
+    /// Write segments named after `path`, which is never written itself.
+    pub fn new<P: AsRef<Path>>(path: P) -> Result<Self, JsonlError> {
+        Ok(Self {
+            naming: Naming::new(path.as_ref())?,
+            max_size: None,
+            max_records: None,
+            period: None,
+            compress: false,
+            keep_segments: None,
+            max_age: None,
+            clock: SystemTime::now,
+            current: None,
+            buf: Vec::new(),
+        })
+    }
+
+    /// Start a new segment rather than let the current one grow past
+    /// `bytes`. A single record larger than that gets a segment of its own.
+    pub fn max_size(mut self, bytes: u64) -> Self {
+        self.max_size = Some(bytes);
+        self
+    }
+
+    /// Start a new segment after `records` records.
+    pub fn max_records(mut self, records: u64) -> Self {
+        self.max_records = Some(records);
+        self
+    }
+
+    /// Start a new segment at every boundary of `period`, and name segments
+    /// after their period.
+    pub fn period(mut self, period: Period) -> Self {
+        self.period = Some(period);
+        self
+    }
+
+    /// Gzip closed segments, adding `.gz` to their name.
+    #[cfg(feature = "gzip")]
+    pub fn compress(mut self, compress: bool) -> Self {
+        self.compress = compress;
+        self
+    }
+
+    /// Delete the oldest closed segments so that at most `segments` remain,
+    /// counting the one being written.
+    pub fn keep_segments(mut self, segments: usize) -> Self {
+        self.keep_segments = Some(segments);
+        self
+    }
+
+    /// Delete closed segments last written more than `age` ago.
+    pub fn max_age(mut self, age: Duration) -> Self {
+        self.max_age = Some(age);
+        self
+    }
+
+    /// Read the time deciding periods and segment names from `clock`
+    /// instead of the system clock, e.g. to file replayed events under
+    /// their own timestamps.
+    pub fn clock(mut self, clock: fn() -> SystemTime) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Serialize `record` and write it, starting a new segment first if
+    /// the current one is full or its period is over.
+    ///
+    /// Nothing is written if the record fails to serialize.
+    pub async fn write<T: Serialize + ?Sized>(&mut self, record: &T) -> Result<(), JsonlError> {
+        self.buf.clear();
+        Framing::Lines
+            .encode(&mut self.buf, record)
+            .map_err(JsonlError::Serialize)?;
+        let len = self.buf.len() as u64;
+        let now = epoch_seconds((self.clock)());
+
+        if let Some(segment) = &self.current {
+            let full = self
+                .max_size
+                .is_some_and(|max| segment.size > 0 && segment.size + len > max)
+                || self.max_records.is_some_and(|max| segment.records >= max)
+                || self.period_of(now) != segment.period;
+            if full {
+                self.close().await?;
+            }
+        }
+        let segment = match self.current.take() {
+            Some(segment) => segment,
+            None => self.open(now).await?,
+        };
+        let segment = self.current.insert(segment);
+
+        segment.file.write_all(&self.buf).await?;
+        segment.size += len;
+        segment.records += 1;
+        Ok(())
+    }
+
+    /// Flush the current segment.
+    pub async fn flush(&mut self) -> Result<(), JsonlError> {
+        if let Some(segment) = &mut self.current {
+            segment.file.flush().await?;
+        }
+        Ok(())
+    }
+
+    /// Close the current segment, compressing it if configured.
+    pub async fn finish(mut self) -> Result<(), JsonlError> {
+        self.close().await
+    }
+
+    /// List the segments of `path` on disk, oldest first.
+    pub async fn segments<P: AsRef<Path>>(path: P) -> Result<Vec<PathBuf>, JsonlError> {
+        let segments = Naming::new(path.as_ref())?.list().await?;
+        Ok(segments.into_iter().map(|segment| segment.path).collect())
+    }
+
+    fn period_of(&self, now: u64) -> u64 {
+        self.period.map_or(0, |period| now / period.seconds())
+    }
+
+    async fn open(&self, now: u64) -> Result<Segment, JsonlError> {
+        let stamp = self.period.unwrap_or(Period::Hour).stamp(now);
+        let existing = self.naming.list().await?;
+        let index = existing
+            .iter()
+            .filter(|segment| segment.name.stamp == stamp)
+            .map(|segment| segment.name.index + 1)
+            .max()
+            .unwrap_or(0);
+        let path = self.naming.path(&SegmentName { stamp, index });
+
+        // Pruning first leaves no stray empty segment behind when it fails
+        self.prune(existing).await?;
+        let file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)
+            .await?;
+        Ok(Segment {
+            file: BufWriter::new(file),
+            path,
+            size: 0,
+            records: 0,
+            period: self.period_of(now),
+        })
+    }
+
+    async fn close(&mut self) -> Result<(), JsonlError> {
+        let Some(mut segment) = self.current.take() else {
+            return Ok(());
+        };
+        segment.file.shutdown().await?;
+        if self.compress {
+            compress(&segment.path).await?;
+        }
+        Ok(())
+    }
+
+    /// Delete the closed segments no longer kept by count or by age.
+    async fn prune(&self, closed: Vec<SegmentFile>) -> Result<(), JsonlError> {
+        let mut expired = 0;
+        if let Some(segments) = self.keep_segments {
+            expired = closed.len().saturating_sub(segments.saturating_sub(1));
+        }
+        let now = SystemTime::now();
+        for (position, segment) in closed.iter().enumerate() {
+            let too_old = match self.max_age {
+                Some(age) => tokio::fs::metadata(&segment.path)
+                    .await?
+                    .modified()?
+                    .checked_add(age)
+                    .is_some_and(|expiry| expiry < now),
+                None => false,
+            };
+            if position < expired || too_old {
+                tokio::fs::remove_file(&segment.path).await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "gzip")]
+async fn compress(path: &Path) -> io::Result<()> {
+    use async_compression::tokio::write::GzipEncoder;
+
+    let mut compressed = path.as_os_str().to_owned();
+    compressed.push(".gz");
+    let mut temp = compressed.clone();
+    temp.push(".tmp");
+
+    let mut source = File::open(path).await?;
+    let mut encoder = GzipEncoder::new(BufWriter::new(File::create(&temp).await?));
+    tokio::io::copy(&mut source, &mut encoder).await?;
+    encoder.shutdown().await?;
+    tokio::fs::rename(&temp, &compressed).await?;
+    tokio::fs::remove_file(path).await
+}
+
+#[cfg(not(feature = "gzip"))]
+async fn compress(_: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+type SegmentReader = Pin<Box<dyn AsyncRead + Send>>;
+
+enum SegmentState {
+    Idle,
+    Opening(BoxFuture<'static, io::Result<SegmentReader>>),
+    Reading(SegmentReader),
+}
+
+/// Reads the segments written by a [`RotatingJsonlWriter`] one after the
+/// other, created by [`Jsonl::from_segments`].
+///
+/// Compressed segments are decompressed, which needs the `gzip` feature. A
+/// segment not ending with a newline is given one, so its last record is
+/// not glued to the first record of the next.
+pub struct Segments {
+    pending: VecDeque<SegmentFile>,
+    state: SegmentState,
+    /// Whether the bytes handed out so far end with a newline
+    line_ended: bool,
+}
+
+impl Jsonl<Segments> {
+    /// Read the segments of `path` written by a [`RotatingJsonlWriter`],
+    /// oldest first, as one JSONL source.
+    ///
+    /// The segments are listed when called; records written later to the
+    /// last of them are read too, but segments started later are not.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use async_jsonl::{Jsonl, JsonlDeserialize};
+    /// use futures::StreamExt;
+    /// use serde_json::Value;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> anyhow::Result<()> {
+    ///     let mut events = Jsonl::from_segments("logs/events.jsonl")
+    ///         .await?
+    ///         .deserialize::<Value>();
+    ///
+    ///     while let Some(event) = events.next().await {
+    ///         println!("{}", event?);
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn from_segments<P: AsRef<Path>>(path: P) -> Result<Self, JsonlError> {
+        let segments = Naming::new(path.as_ref())?.list().await?;
+        Ok(Jsonl::new(Segments {
+            pending: segments.into(),
+            state: SegmentState::Idle,
+            line_ended: true,
+        }))
+    }
+}
+
+async fn open_segment(segment: SegmentFile) -> io::Result<SegmentReader> {
+    let file = File::open(&segment.path).await?;
+    if !segment.compressed {
+        return Ok(Box::pin(file));
+    }
+
+    #[cfg(feature = "gzip")]
+    {
+        let mut decoder =
+            async_compression::tokio::bufread::GzipDecoder::new(tokio::io::BufReader::new(file));
+        decoder.multiple_members(true);
+        Ok(Box::pin(decoder))
+    }
+    #[cfg(not(feature = "gzip"))]
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        format!(
+            "{} is compressed; enable the gzip feature to read it",
+            segment.path.display()
+        ),
+    ))
+}
+
+impl AsyncRead for Segments {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = &mut *self;
+        if buf.remaining() == 0 {
+            return Poll::Ready(Ok(()));
+        }
+        loop {
+            match &mut this.state {
+                SegmentState::Idle => match this.pending.pop_front() {
+                    Some(segment) => {
+                        this.state = SegmentState::Opening(open_segment(segment).boxed());
+                    }
+                    None => return Poll::Ready(Ok(())),
+                },
+                SegmentState::Opening(opening) => {
+                    let reader = ready!(opening.as_mut().poll(cx))?;
+                    this.state = SegmentState::Reading(reader);
+                }
+                SegmentState::Reading(reader) => {
+                    let filled = buf.filled().len();
+                    ready!(reader.as_mut().poll_read(cx, buf))?;
+                    if let Some(&last) = buf.filled()[filled..].last() {
+                        this.line_ended = last == b'\n';
+                        return Poll::Ready(Ok(()));
+                    }
+                    this.state = SegmentState::Idle;
+                    if !this.line_ended {
+                        this.line_ended = true;
+                        buf.put_slice(b"\n");
+                        return Poll::Ready(Ok(()));
+                    }
+                }
+            }
+        }
+    }
+}
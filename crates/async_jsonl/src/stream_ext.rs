@@ -0,0 +1,175 @@
+use crate::builder::{LineAction, ReadOptions};
+use crate::encoding::UTF8_BOM;
+use crate::line_reader::{LineStream, Source};
+use crate::{Jsonl, JsonlError, Location};
+use bytes::Bytes;
+use futures::Stream;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+use tokio::io::AsyncBufRead;
+
+mod sealed {
+    pub trait Sealed {}
+
+    impl Sealed for String {}
+    impl Sealed for bytes::Bytes {}
+}
+
+/// Line types accepted by [`JsonlStreamExt`]: `String` and `Bytes`.
+pub trait LineItem: sealed::Sealed + AsRef<[u8]> {
+    /// Turn a line already checked to be valid UTF-8 into a `String`.
+    #[doc(hidden)]
+    fn into_string(self) -> String;
+}
+
+impl LineItem for String {
+    fn into_string(self) -> String {
+        self
+    }
+}
+
+impl LineItem for Bytes {
+    fn into_string(self) -> String {
+        String::from_utf8_lossy(&self).into_owned()
+    }
+}
+
+/// Gives streams of lines the same deserialization methods as [`Jsonl`].
+///
+/// Implemented for every stream of `Result<String, E>` or `Result<Bytes, E>`
+/// whose error converts into [`JsonlError`], such as lines received from a
+/// channel, a websocket or a decompressor. Each item is one line, with or
+/// without its terminator.
+///
+/// # Examples
+///
+/// ```ignore
+/// use async_jsonl::{JsonlDeserialize, JsonlStreamExt};
+/// use futures::StreamExt;
+/// use serde_json::Value;
+///
+/// #[tokio::main]
+/// async fn main() -> anyhow::Result<()> {
+///     let (sender, receiver) = futures::channel::mpsc::unbounded::<std::io::Result<String>>();
+///     sender.unbounded_send(Ok(r#"{"id": 1}"#.to_string()))?;
+///     drop(sender);
+///
+///     let mut values = receiver.jsonl().deserialize::<Value>();
+///     while let Some(value) = values.next().await {
+///         println!("{}", value?);
+///     }
+///     Ok(())
+/// }
+/// ```
+pub trait JsonlStreamExt: Stream + Sized {
+    /// Treat the items of this stream as JSONL lines, using the default settings.
+    fn jsonl(self) -> JsonlLines<Self> {
+        JsonlLines::new(self, ReadOptions::default())
+    }
+}
+
+impl<S, L, E> JsonlStreamExt for S
+where
+    S: Stream<Item = Result<L, E>>,
+    L: LineItem,
+    E: Into<JsonlError>,
+{
+}
+
+/// Gives any buffered async reader the JSONL reading methods.
+///
+/// Lines are split straight out of the reader's own buffer, so no second
+/// read buffer is put in front of it. The input is read as UTF-8 with the
+/// default settings.
+pub trait JsonlBufReadExt: AsyncBufRead + Unpin + Sized {
+    /// Read this source as JSONL, using the default settings.
+    fn jsonl(self) -> Jsonl<Self> {
+        Jsonl::with_source(Source::direct(self), ReadOptions::default())
+    }
+}
+
+impl<R: AsyncBufRead + Unpin> JsonlBufReadExt for R {}
+
+/// Stream of JSONL lines from a user stream, created by [`JsonlStreamExt::jsonl`].
+///
+/// Lines go through the same trimming, comment, blank line and UTF-8
+/// handling as with [`Jsonl`]. Errors carry the 1-based number of the item
+/// they come from; byte offsets are unknown.
+pub struct JsonlLines<S> {
+    inner: S,
+    options: ReadOptions,
+    line: u64,
+}
+
+impl<S> JsonlLines<S> {
+    pub(crate) fn new(inner: S, options: ReadOptions) -> Self {
+        Self {
+            inner,
+            options,
+            line: 0,
+        }
+    }
+
+    /// Consume the adapter, returning the underlying stream.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S, L, E> LineStream for JsonlLines<S>
+where
+    S: Stream<Item = Result<L, E>> + Unpin,
+    L: LineItem,
+    E: Into<JsonlError>,
+{
+    fn poll_next_line(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<(String, Location), JsonlError>>> {
+        loop {
+            let item = match ready!(Pin::new(&mut self.inner).poll_next(cx)) {
+                Some(Ok(item)) => item,
+                Some(Err(e)) => return Poll::Ready(Some(Err(e.into()))),
+                None => return Poll::Ready(None),
+            };
+            self.line += 1;
+            let location = Location {
+                line: Some(self.line),
+                offset: None,
+            };
+
+            let raw = item.as_ref();
+            let len = raw.len();
+            let raw = raw.strip_suffix(b"\n").unwrap_or(raw);
+            let raw = raw.strip_suffix(b"\r").unwrap_or(raw);
+            let raw = match self.line {
+                1 => raw.strip_prefix(UTF8_BOM).unwrap_or(raw),
+                _ => raw,
+            };
+            let rewritten = match self.options.process(raw, location, false) {
+                // Nothing was trimmed off, so the item itself can be handed out
+                LineAction::Borrowed(line) if line.len() == len => None,
+                LineAction::Borrowed(line) => Some(line.to_string()),
+                LineAction::Owned(line) => Some(line),
+                LineAction::Skip => continue,
+                LineAction::Reject(e) => return Poll::Ready(Some(Err(e))),
+            };
+            let line = rewritten.unwrap_or_else(|| item.into_string());
+            return Poll::Ready(Some(Ok((line, location))));
+        }
+    }
+}
+
+impl<S, L, E> Stream for JsonlLines<S>
+where
+    S: Stream<Item = Result<L, E>> + Unpin,
+    L: LineItem,
+    E: Into<JsonlError>,
+{
+    type Item = Result<String, JsonlError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.poll_next_line(cx)
+            .map(|line| line.map(|result| result.map(|(line, _)| line)))
+    }
+}
@@ -0,0 +1,161 @@
+use crate::lazy::{as_str, peek};
+use crate::line_reader::LineStream;
+use crate::{JsonlError, LazyRecord, Location};
+use futures::future::BoxFuture;
+use futures::Stream;
+use serde::Deserialize;
+use serde_json::value::RawValue;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+
+/// What a registered type makes of a record.
+enum Outcome<E> {
+    Ready(E),
+    Running(BoxFuture<'static, Result<E, JsonlError>>),
+}
+
+type Route<E> = Box<dyn FnMut(&str, Location) -> Result<Outcome<E>, JsonlError> + Send>;
+type Fallback<E> = Box<dyn FnMut(LazyRecord) -> Result<E, JsonlError> + Send>;
+
+/// Stream dispatching records to a type chosen by their tag, created by
+/// [`JsonlDeserialize::deserialize_tagged`](crate::JsonlDeserialize::deserialize_tagged).
+///
+/// The tag is a string under a top-level key of each record. It is found by
+/// skimming over the record without building values, then the record is
+/// deserialized straight into the type registered for its tag and turned
+/// into an `E`. Records are handled one at a time, in order, including
+/// those going through async handlers.
+///
+/// Records whose tag has no registered type, or that have no tag, go to the
+/// [`fallback`](TaggedStream::fallback) or are reported as
+/// [`JsonlError::UnknownTag`]. So do records whose tag is not a string,
+/// reported as [`JsonlError::InvalidTag`] instead.
+pub struct TaggedStream<S, E> {
+    lines: S,
+    tag: String,
+    routes: HashMap<String, Route<E>>,
+    fallback: Option<Fallback<E>>,
+    /// Async handler working on the current record
+    running: Option<BoxFuture<'static, Result<E, JsonlError>>>,
+}
+
+impl<S, E> TaggedStream<S, E> {
+    pub(crate) fn new(lines: S, tag: &str) -> Self {
+        Self {
+            lines,
+            tag: tag.to_string(),
+            routes: HashMap::new(),
+            fallback: None,
+            running: None,
+        }
+    }
+
+    /// Deserialize records tagged `value` into `T`, yielding them as `E`.
+    ///
+    /// Registering the same tag again replaces the previous type.
+    pub fn on<T>(self, value: &str) -> Self
+    where
+        T: for<'a> Deserialize<'a> + Into<E>,
+    {
+        self.route(value, |record: T| Ok(Outcome::Ready(record.into())))
+    }
+
+    /// Deserialize records tagged `value` into `T` and pass them to
+    /// `handler`, yielding what it returns once it completes.
+    pub fn handle<T, F, Fut>(self, value: &str, mut handler: F) -> Self
+    where
+        T: for<'a> Deserialize<'a>,
+        F: FnMut(T) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<E, JsonlError>> + Send + 'static,
+    {
+        self.route(value, move |record: T| {
+            Ok(Outcome::Running(Box::pin(handler(record))))
+        })
+    }
+
+    /// Hand records with an unregistered tag, a tag that is not a string, or
+    /// without a tag, to `fallback` instead of reporting them as errors.
+    pub fn fallback<F>(mut self, fallback: F) -> Self
+    where
+        F: FnMut(LazyRecord) -> Result<E, JsonlError> + Send + 'static,
+    {
+        self.fallback = Some(Box::new(fallback));
+        self
+    }
+
+    fn route<T, F>(mut self, value: &str, mut then: F) -> Self
+    where
+        T: for<'a> Deserialize<'a>,
+        F: FnMut(T) -> Result<Outcome<E>, JsonlError> + Send + 'static,
+    {
+        let route = move |line: &str, location: Location| {
+            let record = serde_json::from_str::<T>(line)
+                .map_err(|e| JsonlError::parse(location, line.as_bytes(), e))?;
+            then(record)
+        };
+        self.routes.insert(value.to_string(), Box::new(route));
+        self
+    }
+
+    fn dispatch(&mut self, line: &str, location: Location) -> Result<Outcome<E>, JsonlError> {
+        let parse_error = |e| JsonlError::parse(location, line.as_bytes(), e);
+        // A tag that isn't a string is kept raw, as it can match no type
+        let tag = peek(line, &self.tag)
+            .map_err(parse_error)?
+            .map(|raw| as_str(raw).ok_or(raw));
+        if let Some(route) = match &tag {
+            Some(Ok(tag)) => self.routes.get_mut(tag.as_ref()),
+            _ => None,
+        } {
+            return route(line, location);
+        }
+        match &mut self.fallback {
+            Some(fallback) => {
+                let raw = serde_json::from_str::<Box<RawValue>>(line).map_err(parse_error)?;
+                fallback(LazyRecord::new(raw, location)).map(Outcome::Ready)
+            }
+            None => Err(match tag {
+                Some(Ok(tag)) => JsonlError::UnknownTag {
+                    location,
+                    tag: Some(tag.into_owned()),
+                },
+                Some(Err(raw)) => JsonlError::InvalidTag {
+                    location,
+                    value: raw.get().to_string(),
+                },
+                None => JsonlError::UnknownTag {
+                    location,
+                    tag: None,
+                },
+            }),
+        }
+    }
+}
+
+impl<S: LineStream, E> Stream for TaggedStream<S, E> {
+    type Item = Result<E, JsonlError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        loop {
+            if let Some(running) = &mut this.running {
+                let result = ready!(running.as_mut().poll(cx));
+                this.running = None;
+                return Poll::Ready(Some(result));
+            }
+
+            let (line, location) = match ready!(this.lines.poll_next_line(cx)) {
+                Some(Ok(line)) => line,
+                Some(Err(e)) => return Poll::Ready(Some(Err(e))),
+                None => return Poll::Ready(None),
+            };
+            match this.dispatch(&line, location) {
+                Ok(Outcome::Ready(record)) => return Poll::Ready(Some(Ok(record))),
+                Ok(Outcome::Running(handler)) => this.running = Some(handler),
+                Err(e) => return Poll::Ready(Some(Err(e))),
+            }
+        }
+    }
+}
@@ -1,68 +1,114 @@
-use crate::take_n::{TakeNLines, TakeNLinesReverse};
-use crate::{Jsonl, JsonlDeserialize, JsonlValueDeserialize};
+use crate::line_reader::LineStream;
+use crate::{
+    DeserializeStream, ErrorPolicy, JsonlDeserialize, JsonlError, JsonlValueDeserialize,
+    LazyRecord, Location, TaggedStream,
+};
 use futures::{Stream, StreamExt};
+use serde::de::DeserializeSeed;
 use serde::Deserialize;
+use serde_json::value::RawValue;
 use serde_json::Value;
-use tokio::io::AsyncRead;
 
-impl<R: AsyncRead + Unpin> JsonlDeserialize for Jsonl<R> {
-    fn deserialize<T>(self) -> impl Stream<Item = anyhow::Result<T>>
-    where
-        T: for<'a> Deserialize<'a>,
-    {
-        self.map(|result| {
-            result.and_then(|line| {
-                serde_json::from_str::<T>(&line)
-                    .map_err(|e| anyhow::anyhow!("Failed to parse JSON line: {}", e))
-            })
+/// Turn every line of `lines` into a record with `parse`.
+fn map_lines<S, T, F>(mut lines: S, mut parse: F) -> impl Stream<Item = Result<T, JsonlError>>
+where
+    S: LineStream,
+    F: FnMut(String, Location) -> Result<T, JsonlError>,
+{
+    futures::stream::poll_fn(move |cx| {
+        lines.poll_next_line(cx).map(|line| {
+            line.map(|result| result.and_then(|(line, location)| parse(line, location)))
         })
-    }
+    })
 }
 
-impl<R: AsyncRead + Unpin> JsonlValueDeserialize for Jsonl<R> {
-    fn deserialize_values(self) -> impl Stream<Item = anyhow::Result<Value>> {
-        self.deserialize::<Value>()
-    }
+/// Parse every line of `lines` as `T`, reporting failures with the line's location.
+fn parse_lines<S, T>(lines: S) -> impl Stream<Item = Result<T, JsonlError>>
+where
+    S: LineStream,
+    T: for<'a> Deserialize<'a>,
+{
+    map_lines(lines, |line, location| {
+        serde_json::from_str::<T>(&line)
+            .map_err(|e| JsonlError::parse(location, line.as_bytes(), e))
+    })
+}
+
+/// Parse every line of `lines` with a seed from `factory`.
+fn parse_lines_seeded<S, F, D, T>(
+    lines: S,
+    mut factory: F,
+) -> impl Stream<Item = Result<T, JsonlError>>
+where
+    S: LineStream,
+    F: FnMut() -> D,
+    D: for<'a> DeserializeSeed<'a, Value = T>,
+{
+    map_lines(lines, move |line, location| {
+        let mut deserializer = serde_json::Deserializer::from_str(&line);
+        factory()
+            .deserialize(&mut deserializer)
+            .and_then(|record| deserializer.end().map(|()| record))
+            .map_err(|e| JsonlError::parse(location, line.as_bytes(), e))
+    })
 }
 
-// Implementations for TakeNLines
-impl<R: AsyncRead + Unpin> JsonlDeserialize for TakeNLines<R> {
-    fn deserialize<T>(self) -> impl Stream<Item = anyhow::Result<T>>
+/// Validate every line of `lines` as JSON, keeping its text and location.
+fn lazy_lines<S: LineStream>(lines: S) -> impl Stream<Item = Result<LazyRecord, JsonlError>> {
+    map_lines(lines, |line, location| {
+        serde_json::from_str::<Box<RawValue>>(&line)
+            .map(|raw| LazyRecord::new(raw, location))
+            .map_err(|e| JsonlError::parse(location, line.as_bytes(), e))
+    })
+}
+
+// Every line source of the crate, including the adapter over user streams,
+// gets deserialization from these two impls.
+impl<S: LineStream> JsonlDeserialize for S {
+    fn deserialize<T>(self) -> impl Stream<Item = Result<T, JsonlError>>
     where
         T: for<'a> Deserialize<'a>,
     {
-        self.map(|result| {
-            result.and_then(|line| {
-                serde_json::from_str::<T>(&line)
-                    .map_err(|e| anyhow::anyhow!("Failed to parse JSON line: {}", e))
-            })
-        })
+        parse_lines(self)
     }
-}
 
-impl<R: AsyncRead + Unpin> JsonlValueDeserialize for TakeNLines<R> {
-    fn deserialize_values(self) -> impl Stream<Item = anyhow::Result<Value>> {
-        self.deserialize::<Value>()
+    fn deserialize_with<T>(self, policy: ErrorPolicy) -> DeserializeStream<Self, T>
+    where
+        T: for<'a> Deserialize<'a>,
+    {
+        DeserializeStream::new(self, policy)
     }
-}
 
-// Implementations for TakeNLinesReverse
-impl JsonlDeserialize for TakeNLinesReverse {
-    fn deserialize<T>(self) -> impl Stream<Item = anyhow::Result<T>>
+    fn deserialize_seeded<D, T>(self, seed: D) -> impl Stream<Item = Result<T, JsonlError>>
     where
-        T: for<'a> Deserialize<'a>,
+        D: for<'a> DeserializeSeed<'a, Value = T> + Clone,
     {
-        self.map(|result| {
-            result.and_then(|line| {
-                serde_json::from_str::<T>(&line)
-                    .map_err(|e| anyhow::anyhow!("Failed to parse JSON line: {}", e))
-            })
-        })
+        parse_lines_seeded(self, move || seed.clone())
+    }
+
+    fn deserialize_seeded_by<F, D, T>(self, factory: F) -> impl Stream<Item = Result<T, JsonlError>>
+    where
+        F: FnMut() -> D,
+        D: for<'a> DeserializeSeed<'a, Value = T>,
+    {
+        parse_lines_seeded(self, factory)
+    }
+
+    fn deserialize_tagged<E>(self, tag: &str) -> TaggedStream<Self, E> {
+        TaggedStream::new(self, tag)
     }
 }
 
-impl JsonlValueDeserialize for TakeNLinesReverse {
-    fn deserialize_values(self) -> impl Stream<Item = anyhow::Result<Value>> {
+impl<S: LineStream> JsonlValueDeserialize for S {
+    fn deserialize_values(self) -> impl Stream<Item = Result<Value, JsonlError>> {
         self.deserialize::<Value>()
     }
+
+    fn deserialize_raw(self) -> impl Stream<Item = Result<Box<RawValue>, JsonlError>> {
+        lazy_lines(self).map(|record| record.map(LazyRecord::into_raw))
+    }
+
+    fn deserialize_lazy(self) -> impl Stream<Item = Result<LazyRecord, JsonlError>> {
+        lazy_lines(self)
+    }
 }
@@ -0,0 +1,217 @@
+use crate::{Framing, JsonlError};
+use futures::{Sink, Stream, StreamExt};
+use serde::Serialize;
+use std::io;
+use std::pin::{pin, Pin};
+use std::task::{ready, Context, Poll};
+use tokio::io::AsyncWrite;
+
+/// Writes records as JSON lines, one serialized record per line.
+///
+/// Records are serialized compactly, so each one takes exactly one line with
+/// no line breaks inside it, and are handed to the underlying writer whole.
+/// Wrap unbuffered sinks such as files in a [`tokio::io::BufWriter`].
+///
+/// `JsonlWriter` is also a [`Sink`] of any serializable record, so the
+/// streams returned by [`deserialize`](crate::JsonlDeserialize::deserialize)
+/// can be `forward`ed into it.
+///
+/// # Examples
+///
+/// ```ignore
+/// use async_jsonl::JsonlWriter;
+/// use serde::Serialize;
+/// use tokio::io::BufWriter;
+///
+/// #[derive(Serialize)]
+/// struct Header {
+///     schema_version: u32,
+/// }
+///
+/// #[derive(Serialize)]
+/// struct Sample {
+///     value: f64,
+/// }
+///
+/// #[tokio::main]
+/// async fn main() -> anyhow::Result<()> {
+///     let file = BufWriter::new(tokio::fs::File::create("dataset.jsonl").await?);
+///     let mut writer = JsonlWriter::new(file)
+///         .with_header(&Header { schema_version: 2 })
+///         .await?;
+///
+///     for value in [0.5, 1.5] {
+///         writer.write(&Sample { value }).await?;
+///     }
+///     writer.finish().await?;
+///     Ok(())
+/// }
+/// ```
+///
+/// Copying records between files:
+///
+/// ```ignore
+/// use async_jsonl::{Jsonl, JsonlDeserialize, JsonlWriter};
+/// use futures::StreamExt;
+/// use serde_json::Value;
+/// use tokio::io::BufWriter;
+///
+/// #[tokio::main]
+/// async fn main() -> anyhow::Result<()> {
+///     let records = Jsonl::from_path("in.jsonl").await?.deserialize::<Value>();
+///     let file = BufWriter::new(tokio::fs::File::create("out.jsonl").await?);
+///
+///     records.forward(JsonlWriter::new(file)).await?;
+///     Ok(())
+/// }
+/// ```
+pub struct JsonlWriter<W> {
+    writer: W,
+    framing: Framing,
+    /// Serialized records not yet handed to the writer
+    buf: Vec<u8>,
+    /// How much of `buf` the writer has taken
+    written: usize,
+}
+
+impl<W: AsyncWrite + Unpin> JsonlWriter<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            framing: Framing::default(),
+            buf: Vec::new(),
+            written: 0,
+        }
+    }
+
+    /// Set how records are delimited, one per line by default.
+    pub fn framing(mut self, framing: Framing) -> Self {
+        self.framing = framing;
+        self
+    }
+
+    /// Write `header` as the first record, for readers using
+    /// [`Jsonl::read_header`](crate::Jsonl::read_header).
+    ///
+    /// Must be called before any other record is written.
+    pub async fn with_header<H: Serialize + ?Sized>(
+        mut self,
+        header: &H,
+    ) -> Result<Self, JsonlError> {
+        self.write(header).await?;
+        Ok(self)
+    }
+
+    /// Serialize `record` and write it, followed by its terminator.
+    ///
+    /// Nothing is written if the record fails to serialize. If the returned
+    /// future is dropped early, the rest of the record is written before
+    /// anything else.
+    pub async fn write<T: Serialize + ?Sized>(&mut self, record: &T) -> Result<(), JsonlError> {
+        self.encode(record)?;
+        futures::future::poll_fn(|cx| self.poll_drain(cx)).await?;
+        Ok(())
+    }
+
+    /// Write every record of `records`, returning how many were written.
+    ///
+    /// Stops at the first error of `records` and returns it.
+    pub async fn write_all<S, T>(&mut self, records: S) -> Result<usize, JsonlError>
+    where
+        S: Stream<Item = Result<T, JsonlError>>,
+        T: Serialize,
+    {
+        let mut records = pin!(records);
+        let mut written = 0;
+        while let Some(record) = records.next().await {
+            self.write(&record?).await?;
+            written += 1;
+        }
+        Ok(written)
+    }
+
+    /// Flush the underlying writer.
+    pub async fn flush(&mut self) -> Result<(), JsonlError> {
+        futures::future::poll_fn(|cx| self.poll_flush_inner(cx)).await?;
+        Ok(())
+    }
+
+    /// Write out everything pending, then shut the underlying writer down,
+    /// returning it.
+    pub async fn finish(mut self) -> Result<W, JsonlError> {
+        futures::future::poll_fn(|cx| self.poll_close_inner(cx)).await?;
+        Ok(self.writer)
+    }
+
+    /// Get a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    /// Get a mutable reference to the underlying writer.
+    ///
+    /// Writing to it directly may interleave with records still pending.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    /// Consume the writer, returning the underlying writer.
+    ///
+    /// Records not yet taken by the underlying writer are lost.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    /// Serialize `record` after the pending records.
+    fn encode<T: Serialize + ?Sized>(&mut self, record: &T) -> Result<(), JsonlError> {
+        let len = self.buf.len();
+        self.framing.encode(&mut self.buf, record).map_err(|e| {
+            self.buf.truncate(len);
+            JsonlError::Serialize(e)
+        })
+    }
+
+    /// Hand every pending byte to the underlying writer.
+    fn poll_drain(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        while self.written < self.buf.len() {
+            let n = ready!(Pin::new(&mut self.writer).poll_write(cx, &self.buf[self.written..]))?;
+            if n == 0 {
+                return Poll::Ready(Err(io::ErrorKind::WriteZero.into()));
+            }
+            self.written += n;
+        }
+        self.buf.clear();
+        self.written = 0;
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_flush_inner(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        ready!(self.poll_drain(cx))?;
+        Pin::new(&mut self.writer).poll_flush(cx)
+    }
+
+    fn poll_close_inner(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        ready!(self.poll_drain(cx))?;
+        Pin::new(&mut self.writer).poll_shutdown(cx)
+    }
+}
+
+impl<W: AsyncWrite + Unpin, T: Serialize> Sink<T> for JsonlWriter<W> {
+    type Error = JsonlError;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), JsonlError>> {
+        self.get_mut().poll_drain(cx).map_err(Into::into)
+    }
+
+    fn start_send(self: Pin<&mut Self>, record: T) -> Result<(), JsonlError> {
+        self.get_mut().encode(&record)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), JsonlError>> {
+        self.get_mut().poll_flush_inner(cx).map_err(Into::into)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), JsonlError>> {
+        self.get_mut().poll_close_inner(cx).map_err(Into::into)
+    }
+}
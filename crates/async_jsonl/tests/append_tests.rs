@@ -0,0 +1,138 @@
+mod common;
+
+use async_jsonl::{Jsonl, JsonlError, JsonlSeekReader, JsonlWriter, TornTailPolicy};
+use common::scratch_dir;
+use futures::StreamExt;
+use serde_json::json;
+use std::path::Path;
+
+async fn append_one(path: &Path, policy: TornTailPolicy) -> Result<(), JsonlError> {
+    let mut writer = JsonlWriter::open_append(path, policy).await?;
+    writer.write(&json!({"id": 9})).await?;
+    writer.flush().await
+}
+
+#[tokio::test]
+async fn test_clean_and_missing_files_are_appended_to() {
+    let dir = scratch_dir("clean");
+    let path = dir.join("log.jsonl");
+
+    append_one(&path, TornTailPolicy::Refuse).await.unwrap();
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "{\"id\":9}\n");
+
+    // Trailing blank lines are not a torn record
+    std::fs::write(&path, "{\"id\": 1}\r\n\n  \n").unwrap();
+    append_one(&path, TornTailPolicy::Refuse).await.unwrap();
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        "{\"id\": 1}\r\n\n  \n{\"id\":9}\n"
+    );
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_truncate_cuts_off_a_record_without_newline() {
+    let dir = scratch_dir("truncate");
+    let path = dir.join("log.jsonl");
+    std::fs::write(&path, "{\"id\": 1}\n{\"id\": 2}\n{\"id\": 3, \"na").unwrap();
+
+    append_one(&path, TornTailPolicy::Truncate).await.unwrap();
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        "{\"id\": 1}\n{\"id\": 2}\n{\"id\":9}\n"
+    );
+
+    // A complete value may still be the prefix of a longer one
+    std::fs::write(&path, "{\"id\": 1}\n12").unwrap();
+    append_one(&path, TornTailPolicy::default()).await.unwrap();
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        "{\"id\": 1}\n{\"id\":9}\n"
+    );
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_quarantine_keeps_torn_records() {
+    let dir = scratch_dir("quarantine");
+    let path = dir.join("log.jsonl");
+    let quarantine = dir.join("log.jsonl.torn");
+
+    std::fs::write(&path, "{\"id\": 1}\n{\"id\": 2, \"x\": [\n\n").unwrap();
+    append_one(&path, TornTailPolicy::Quarantine(quarantine.clone()))
+        .await
+        .unwrap();
+    std::fs::write(&path, "{\"id\": 1}\n{\"id\"").unwrap();
+    append_one(&path, TornTailPolicy::Quarantine(quarantine.clone()))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        "{\"id\": 1}\n{\"id\":9}\n"
+    );
+    assert_eq!(
+        std::fs::read_to_string(&quarantine).unwrap(),
+        "{\"id\": 2, \"x\": [\n{\"id\"\n"
+    );
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_refuse_leaves_the_file_untouched() {
+    let dir = scratch_dir("refuse");
+    let path = dir.join("log.jsonl");
+    let data = "{\"id\": 1}\n{\"id\": 2}\n{\"id\": 3";
+    std::fs::write(&path, data).unwrap();
+
+    let error = append_one(&path, TornTailPolicy::Refuse).await.unwrap_err();
+    match &error {
+        JsonlError::Truncated { location, snippet } => {
+            assert_eq!(location.offset, Some(20));
+            assert_eq!(snippet, "{\"id\": 3");
+        }
+        other => panic!("expected a torn record, got {:?}", other),
+    }
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), data);
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_last_n_after_repair_starts_at_a_whole_record() {
+    let dir = scratch_dir("last_n");
+    let path = dir.join("log.jsonl");
+    std::fs::write(&path, "{\"id\": 1}\n{\"id\": 2}\n{\"id\": 3, ").unwrap();
+
+    append_one(&path, TornTailPolicy::Truncate).await.unwrap();
+    let last: Vec<String> = Jsonl::from_path(&path)
+        .await
+        .unwrap()
+        .last_n(2)
+        .await
+        .unwrap()
+        .map(Result::unwrap)
+        .collect()
+        .await;
+    assert_eq!(last, vec!["{\"id\":9}", "{\"id\": 2}"]);
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_torn_tails_longer_than_the_check_are_kept_whole() {
+    let dir = scratch_dir("long_tail");
+    let path = dir.join("log.jsonl");
+    let quarantine = dir.join("log.jsonl.torn");
+    let tail = format!("{{\"blob\": \"{}", "x".repeat(65 * 1024 * 1024));
+    std::fs::write(&path, format!("{{\"id\": 1}}\n{}\n", tail)).unwrap();
+
+    // Complete but too long to check, so taken for torn
+    append_one(&path, TornTailPolicy::Quarantine(quarantine.clone()))
+        .await
+        .unwrap();
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        "{\"id\": 1}\n{\"id\":9}\n"
+    );
+    assert!(std::fs::read_to_string(&quarantine).unwrap() == tail + "\n");
+    std::fs::remove_dir_all(dir).unwrap();
+}
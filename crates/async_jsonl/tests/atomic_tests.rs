@@ -0,0 +1,119 @@
+mod common;
+
+use async_jsonl::{AtomicJsonlFile, Jsonl, JsonlDeserialize, JsonlError};
+use common::scratch_dir;
+use futures::StreamExt;
+use serde_json::{json, Value};
+use std::path::Path;
+
+fn entries(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = std::fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    names.sort();
+    names
+}
+
+#[tokio::test]
+async fn test_commit_replaces_the_target() {
+    let dir = scratch_dir("commit");
+    let path = dir.join("data.jsonl");
+    std::fs::write(&path, "{\"id\": 1}\n{\"id\": 2}\n{\"id\": 3}\n").unwrap();
+
+    let records = Jsonl::from_path(&path)
+        .await
+        .unwrap()
+        .deserialize::<Value>()
+        .filter(|record| {
+            let keep = !matches!(record, Ok(value) if value["id"] == 2);
+            async move { keep }
+        });
+    let mut file = AtomicJsonlFile::create(&path).await.unwrap();
+    assert_eq!(file.write_all(records).await.unwrap(), 2);
+    file.commit().await.unwrap();
+
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        "{\"id\":1}\n{\"id\":3}\n"
+    );
+    assert_eq!(entries(&dir), vec!["data.jsonl"]);
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_dropping_without_commit_keeps_the_old_file() {
+    let dir = scratch_dir("drop");
+    let path = dir.join("data.jsonl");
+    std::fs::write(&path, "{\"id\": 1}\n").unwrap();
+
+    let mut file = AtomicJsonlFile::create(&path).await.unwrap();
+    file.write(&json!({"id": 2})).await.unwrap();
+    assert_eq!(entries(&dir).len(), 2);
+    drop(file);
+
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "{\"id\": 1}\n");
+    assert_eq!(entries(&dir), vec!["data.jsonl"]);
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_open_readers_keep_seeing_the_old_file() {
+    let dir = scratch_dir("readers");
+    let path = dir.join("data.jsonl");
+    std::fs::write(&path, "{\"id\": 1}\n{\"id\": 2}\n").unwrap();
+    let old = Jsonl::from_path(&path).await.unwrap();
+
+    let mut file = AtomicJsonlFile::create(&path).await.unwrap();
+    file.write(&json!({"id": 3})).await.unwrap();
+    file.commit().await.unwrap();
+
+    let old: Vec<String> = old.map(Result::unwrap).collect().await;
+    assert_eq!(old, vec!["{\"id\": 1}", "{\"id\": 2}"]);
+    let new: Vec<String> = Jsonl::from_path(&path)
+        .await
+        .unwrap()
+        .map(Result::unwrap)
+        .collect()
+        .await;
+    assert_eq!(new, vec!["{\"id\":3}"]);
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_forwarded_records_create_a_new_file() {
+    let dir = scratch_dir("forward");
+    let path = dir.join("new.jsonl");
+
+    let mut file = AtomicJsonlFile::create(&path).await.unwrap();
+    futures::stream::iter((1..=3).map(|id| Ok::<_, JsonlError>(json!({ "id": id }))))
+        .forward(&mut file)
+        .await
+        .unwrap();
+    assert!(!path.exists());
+    file.commit().await.unwrap();
+
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        "{\"id\":1}\n{\"id\":2}\n{\"id\":3}\n"
+    );
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_commit_keeps_the_permissions_of_the_old_file() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = scratch_dir("permissions");
+    let path = dir.join("data.jsonl");
+    std::fs::write(&path, "").unwrap();
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o640)).unwrap();
+
+    let file = AtomicJsonlFile::create(&path).await.unwrap();
+    file.commit().await.unwrap();
+
+    let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o640);
+    std::fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,54 @@
+use async_jsonl::{Jsonl, JsonlBuilder, JsonlError, Utf8Policy};
+use serde::Deserialize;
+use std::borrow::Cow;
+use std::io::Cursor;
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Event<'a> {
+    kind: &'a str,
+    #[serde(borrow)]
+    message: Cow<'a, str>,
+}
+
+#[tokio::test]
+async fn test_next_borrowed() {
+    let data = "{\"kind\": \"a\", \"message\": \"plain\"}\n\n{\"kind\": \"b\", \"message\": \"tab\\there\"}\n";
+    let mut reader = Jsonl::new(Cursor::new(data.as_bytes()));
+
+    let mut kinds = Vec::new();
+    while let Some(event) = reader.next_borrowed::<Event>().await {
+        let event = event.unwrap();
+        kinds.push(event.kind.to_string());
+        match event.kind {
+            "a" => assert!(matches!(event.message, Cow::Borrowed("plain"))),
+            _ => assert_eq!(event.message, "tab\there"),
+        }
+    }
+    assert_eq!(kinds, vec!["a", "b"]);
+}
+
+#[tokio::test]
+async fn test_next_borrowed_reports_errors_and_continues() {
+    let data = "{\"kind\": \"a\", \"message\": \"x\"}\n{\"kind\": 1}\n{\"kind\": \"c\", \"message\": \"y\"}\n";
+    let mut reader = Jsonl::new(Cursor::new(data.as_bytes()));
+
+    assert!(reader.next_borrowed::<Event>().await.unwrap().is_ok());
+    match reader.next_borrowed::<Event>().await.unwrap() {
+        Err(e @ JsonlError::Parse { .. }) => assert_eq!(e.line(), Some(2)),
+        other => panic!("expected parse error, got {:?}", other),
+    }
+    let last = reader.next_borrowed::<Event>().await.unwrap().unwrap();
+    assert_eq!(last.kind, "c");
+    assert!(reader.next_borrowed::<Event>().await.is_none());
+}
+
+#[tokio::test]
+async fn test_next_borrowed_lossy_line() {
+    let data = b"{\"kind\": \"\xff\", \"message\": \"m\"}\n";
+    let mut reader = JsonlBuilder::new()
+        .utf8(Utf8Policy::Lossy)
+        .build(Cursor::new(&data[..]));
+
+    let event = reader.next_borrowed::<Event>().await.unwrap().unwrap();
+    assert_eq!(event.kind, "\u{fffd}");
+}
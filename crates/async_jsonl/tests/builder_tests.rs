@@ -0,0 +1,203 @@
+use async_jsonl::{
+    BlankLinePolicy, JsonlBuilder, JsonlError, JsonlSeekReader, LongLinePolicy, TrimPolicy,
+};
+use futures::StreamExt;
+use std::io::Cursor;
+
+async fn collect<S: futures::Stream<Item = Result<String, JsonlError>>>(
+    stream: S,
+) -> Vec<Result<String, JsonlError>> {
+    stream.collect().await
+}
+
+#[tokio::test]
+async fn test_default_builder_matches_jsonl_new() {
+    let data = "  {\"a\": 1}  \n\n{\"a\": 2}\n";
+
+    let lines = collect(JsonlBuilder::new().build(Cursor::new(data.as_bytes()))).await;
+
+    let lines: Vec<String> = lines.into_iter().map(|r| r.unwrap()).collect();
+    assert_eq!(lines, vec![r#"{"a": 1}"#, r#"{"a": 2}"#]);
+}
+
+#[tokio::test]
+async fn test_trim_policies() {
+    let data = "  {\"a\": 1}  \r\n";
+
+    let none = JsonlBuilder::new().trim(TrimPolicy::None);
+    let end = JsonlBuilder::new().trim(TrimPolicy::End);
+
+    let lines = collect(none.build(Cursor::new(data.as_bytes()))).await;
+    assert_eq!(lines[0].as_ref().unwrap(), "  {\"a\": 1}  ");
+    let lines = collect(end.build(Cursor::new(data.as_bytes()))).await;
+    assert_eq!(lines[0].as_ref().unwrap(), "  {\"a\": 1}");
+}
+
+#[tokio::test]
+async fn test_blank_line_policies() {
+    let data = "{\"a\": 1}\n   \n{\"a\": 2}\n";
+
+    let yield_blank = JsonlBuilder::new().blank_lines(BlankLinePolicy::Yield);
+    let lines = collect(yield_blank.build(Cursor::new(data.as_bytes()))).await;
+    assert_eq!(lines.len(), 3);
+    assert_eq!(lines[1].as_ref().unwrap(), "");
+
+    let reject_blank = JsonlBuilder::new().blank_lines(BlankLinePolicy::Error);
+    let lines = collect(reject_blank.build(Cursor::new(data.as_bytes()))).await;
+    assert_eq!(lines.len(), 3);
+    let error = lines[1].as_ref().unwrap_err();
+    assert!(matches!(error, JsonlError::BlankLine { .. }));
+    assert_eq!(error.line(), Some(2));
+}
+
+#[tokio::test]
+async fn test_comment_prefixes() {
+    let data = "# header\n{\"a\": 1}\n  // note\n{\"a\": 2}\n";
+
+    let builder = JsonlBuilder::new().comment_prefix("#").comment_prefix("//");
+    let lines = collect(builder.build(Cursor::new(data.as_bytes()))).await;
+
+    let lines: Vec<String> = lines.into_iter().map(|r| r.unwrap()).collect();
+    assert_eq!(lines, vec![r#"{"a": 1}"#, r#"{"a": 2}"#]);
+}
+
+#[tokio::test]
+async fn test_max_line_length() {
+    let data = "{\"a\": 1}\n{\"a\": \"long value\"}\n{\"a\": 2}\n";
+
+    let builder = JsonlBuilder::new().max_line_length(10);
+    let lines = collect(builder.build(Cursor::new(data.as_bytes()))).await;
+
+    assert_eq!(lines.len(), 3);
+    assert!(matches!(
+        lines[1],
+        Err(JsonlError::LineTooLong { limit: 10, .. })
+    ));
+    assert_eq!(lines[2].as_ref().unwrap(), r#"{"a": 2}"#);
+}
+
+#[tokio::test]
+async fn test_first_n_and_last_n_share_settings() {
+    let data = "# comment\n{\"a\": 1}\n# comment\n{\"a\": 2}\n{\"a\": 3}\n# trailing\n";
+    let builder = JsonlBuilder::new().comment_prefix("#").buffer_capacity(4);
+
+    let first = collect(builder.first_n(Cursor::new(data.as_bytes()), 2)).await;
+    let first: Vec<String> = first.into_iter().map(|r| r.unwrap()).collect();
+    assert_eq!(first, vec![r#"{"a": 1}"#, r#"{"a": 2}"#]);
+
+    let last = builder
+        .last_n(Cursor::new(data.as_bytes()), 2)
+        .await
+        .unwrap();
+    let last: Vec<String> = collect(last)
+        .await
+        .into_iter()
+        .map(|r| r.unwrap())
+        .collect();
+    assert_eq!(last, vec![r#"{"a": 3}"#, r#"{"a": 2}"#]);
+
+    // Readers built by the builder keep its settings through JsonlReader
+    let last = builder
+        .build(Cursor::new(data.as_bytes()))
+        .last_n(5)
+        .await
+        .unwrap();
+    assert_eq!(collect(last).await.len(), 3);
+}
+
+#[tokio::test]
+async fn test_last_n_blank_line_error_ignores_trailing_newline() {
+    let data = "{\"a\": 1}\n\n{\"a\": 2}\n";
+    let builder = JsonlBuilder::new().blank_lines(BlankLinePolicy::Error);
+
+    let last = collect(
+        builder
+            .last_n(Cursor::new(data.as_bytes()), 3)
+            .await
+            .unwrap(),
+    )
+    .await;
+
+    assert_eq!(last.len(), 3);
+    assert_eq!(last[0].as_ref().unwrap(), r#"{"a": 2}"#);
+    assert!(matches!(last[1], Err(JsonlError::BlankLine { .. })));
+    assert_eq!(last[2].as_ref().unwrap(), r#"{"a": 1}"#);
+}
+
+#[tokio::test]
+async fn test_long_line_without_newline_is_bounded() {
+    // A single huge line followed by a valid one
+    let mut data = vec![b'x'; 1024 * 1024];
+    data.extend_from_slice(b"\n{\"a\": 1}\n");
+    let builder = JsonlBuilder::new().max_line_length(16).buffer_capacity(64);
+
+    let lines = collect(builder.build(Cursor::new(data.clone()))).await;
+    assert_eq!(lines.len(), 2);
+    match &lines[0] {
+        Err(JsonlError::LineTooLong {
+            location, snippet, ..
+        }) => {
+            assert_eq!(location.line, Some(1));
+            assert!(snippet.len() <= 18);
+        }
+        other => panic!("expected LineTooLong, got {:?}", other),
+    }
+    // The reader resyncs at the next newline
+    assert_eq!(lines[1].as_ref().unwrap(), r#"{"a": 1}"#);
+
+    let last = collect(builder.last_n(Cursor::new(data), 2).await.unwrap()).await;
+    assert_eq!(last.len(), 2);
+    assert_eq!(last[0].as_ref().unwrap(), r#"{"a": 1}"#);
+    assert!(matches!(last[1], Err(JsonlError::LineTooLong { .. })));
+    assert_eq!(last[1].as_ref().unwrap_err().offset(), Some(0));
+}
+
+#[tokio::test]
+async fn test_long_line_truncate_policy() {
+    let data = "{\"a\": 1}\né€é€é€é€é€\nshort\n";
+    let builder = JsonlBuilder::new()
+        .max_line_length(8)
+        .long_lines(LongLinePolicy::Truncate);
+
+    let lines = collect(builder.build(Cursor::new(data.as_bytes()))).await;
+    let lines: Vec<String> = lines.into_iter().map(|r| r.unwrap()).collect();
+    // Truncation never splits a multi-byte character
+    assert_eq!(lines, vec![r#"{"a": 1}"#, "é€é", "short"]);
+
+    let last = collect(
+        builder
+            .last_n(Cursor::new(data.as_bytes()), 3)
+            .await
+            .unwrap(),
+    )
+    .await;
+    let last: Vec<String> = last.into_iter().map(|r| r.unwrap()).collect();
+    assert_eq!(last, vec!["short", "é€é", r#"{"a": 1}"#]);
+}
+
+#[tokio::test]
+async fn test_long_line_positions_stay_exact() {
+    let data = format!("{}\n{{\"a\": 1}}\n", "y".repeat(100));
+    let builder = JsonlBuilder::new().max_line_length(10).buffer_capacity(7);
+
+    let lines: Vec<_> = builder
+        .build(Cursor::new(data.into_bytes()))
+        .with_positions()
+        .collect()
+        .await;
+
+    let second = lines[1].as_ref().unwrap();
+    assert_eq!((second.line, second.offset, second.len), (2, 101, 9));
+}
+
+#[tokio::test]
+async fn test_first_n_counts_overlong_lines() {
+    let data = "xxxxxxxx\nyyyyyyyy\nzzzzzzzz\n{}\n";
+    let builder = JsonlBuilder::new().max_line_length(4);
+
+    let lines = collect(builder.first_n(Cursor::new(data.as_bytes()), 2)).await;
+    assert_eq!(lines.len(), 2);
+    assert!(lines
+        .iter()
+        .all(|line| matches!(line, Err(JsonlError::LineTooLong { .. }))));
+}
@@ -0,0 +1,109 @@
+use async_jsonl::{Jsonl, JsonlBuilder, JsonlError, JsonlSeekReader, Utf8Policy};
+use bytes::Bytes;
+use futures::StreamExt;
+use serde::Deserialize;
+use std::io::Cursor;
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct SimpleRecord {
+    value: i32,
+}
+
+const INVALID: &[u8] = b"{\"value\": 1}\n{\"value\": 2, \"bad\": \"\xff\"}\n{\"value\": 3}\n";
+
+#[tokio::test]
+async fn test_bytes_stream() {
+    let data = "  {\"value\": 1}\r\n\n{\"value\": 2}";
+
+    let records: Vec<Bytes> = Jsonl::new(Cursor::new(data.as_bytes()))
+        .bytes()
+        .map(|r| r.unwrap())
+        .collect()
+        .await;
+
+    assert_eq!(
+        records,
+        vec![&b"{\"value\": 1}"[..], &b"{\"value\": 2}"[..]]
+    );
+    let parsed: Vec<SimpleRecord> = records
+        .iter()
+        .map(|record| serde_json::from_slice(record).unwrap())
+        .collect();
+    assert_eq!(
+        parsed,
+        vec![SimpleRecord { value: 1 }, SimpleRecord { value: 2 }]
+    );
+}
+
+#[tokio::test]
+async fn test_bytes_stream_reports_invalid_utf8() {
+    let records: Vec<_> = Jsonl::new(Cursor::new(INVALID)).bytes().collect().await;
+
+    assert_eq!(records.len(), 3);
+    let error = records[1].as_ref().unwrap_err();
+    assert!(matches!(error, JsonlError::InvalidUtf8 { .. }));
+    assert_eq!(error.line(), Some(2));
+    assert_eq!(records[2].as_ref().unwrap(), &b"{\"value\": 3}"[..]);
+}
+
+#[tokio::test]
+async fn test_utf8_policy_is_the_same_in_both_directions() {
+    for (policy, expected) in [
+        (Utf8Policy::Error, 3),
+        (Utf8Policy::Lossy, 3),
+        (Utf8Policy::Skip, 2),
+    ] {
+        let builder = JsonlBuilder::new().utf8(policy);
+
+        let forward: Vec<_> = builder.build(Cursor::new(INVALID)).collect().await;
+        let mut reverse: Vec<_> = builder
+            .last_n(Cursor::new(INVALID), 10)
+            .await
+            .unwrap()
+            .collect()
+            .await;
+        reverse.reverse();
+
+        assert_eq!(forward.len(), expected, "{:?}", policy);
+        assert_eq!(reverse.len(), expected, "{:?}", policy);
+        for (forward, reverse) in forward.iter().zip(&reverse) {
+            match (forward, reverse) {
+                (Ok(forward), Ok(reverse)) => assert_eq!(forward, reverse),
+                (Err(JsonlError::InvalidUtf8 { .. }), Err(JsonlError::InvalidUtf8 { .. })) => {}
+                other => panic!("{:?}: mismatch {:?}", policy, other),
+            }
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_lossy_utf8_replaces_invalid_bytes() {
+    let builder = JsonlBuilder::new().utf8(Utf8Policy::Lossy);
+
+    let lines: Vec<_> = builder.build(Cursor::new(INVALID)).collect().await;
+    assert_eq!(
+        lines[1].as_ref().unwrap(),
+        "{\"value\": 2, \"bad\": \"\u{fffd}\"}"
+    );
+
+    let records: Vec<_> = builder.build(Cursor::new(INVALID)).bytes().collect().await;
+    assert_eq!(
+        records[1].as_ref().unwrap(),
+        "{\"value\": 2, \"bad\": \"\u{fffd}\"}".as_bytes()
+    );
+}
+
+#[tokio::test]
+async fn test_last_n_default_rejects_invalid_utf8() {
+    let last: Vec<_> = Jsonl::new(Cursor::new(INVALID))
+        .last_n(2)
+        .await
+        .unwrap()
+        .collect()
+        .await;
+
+    assert_eq!(last[0].as_ref().unwrap(), r#"{"value": 3}"#);
+    let error = last[1].as_ref().unwrap_err();
+    assert!(matches!(error, JsonlError::InvalidUtf8 { .. }));
+    assert_eq!(error.offset(), Some(13));
+}
@@ -0,0 +1,42 @@
+// Each test binary uses only some of these
+#![allow(dead_code)]
+
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, ReadBuf};
+
+static NEXT_DIR: AtomicUsize = AtomicUsize::new(0);
+
+/// A fresh, empty directory for one test, unique to this run
+pub fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "async_jsonl_{}.{}-{}",
+        name,
+        std::process::id(),
+        NEXT_DIR.fetch_add(1, Ordering::Relaxed)
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Reader that fails after handing out its data.
+pub struct Failing(pub Vec<u8>);
+
+impl AsyncRead for Failing {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        if self.0.is_empty() {
+            return Poll::Ready(Err(std::io::Error::other("disk on fire")));
+        }
+        let len = self.0.len().min(buf.remaining());
+        buf.put_slice(&self.0[..len]);
+        self.0.drain(..len);
+        Poll::Ready(Ok(()))
+    }
+}
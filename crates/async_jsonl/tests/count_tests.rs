@@ -0,0 +1,169 @@
+mod common;
+
+use async_jsonl::{BlankLinePolicy, Jsonl, JsonlBuilder, JsonlError, JsonlReader, Utf8Policy};
+use common::Failing;
+use std::io::Cursor;
+
+const DATA: &str =
+    "{\"a\": 1}\n  \n# comment\n{broken\r\n\t{\"a\": 2}  \n\n  # indented comment\n[1, 2]";
+
+#[tokio::test]
+async fn test_count_reports_io_errors() {
+    let result = JsonlReader::count(Jsonl::new(Failing(b"{\"a\": 1}\n".to_vec()))).await;
+    assert!(matches!(result, Err(JsonlError::Io(_))));
+}
+
+#[tokio::test]
+async fn test_count_skips_comments() {
+    let builder = JsonlBuilder::new().comment_prefix("#");
+    let count = JsonlReader::count(builder.build(Cursor::new(DATA.as_bytes())))
+        .await
+        .unwrap();
+    assert_eq!(count, 4);
+}
+
+#[tokio::test]
+async fn test_count_yielded_blank_lines() {
+    let builder = JsonlBuilder::new().blank_lines(BlankLinePolicy::Yield);
+    let count = JsonlReader::count(builder.build(Cursor::new(DATA.as_bytes())))
+        .await
+        .unwrap();
+    assert_eq!(count, 8);
+}
+
+#[tokio::test]
+async fn test_count_across_small_buffers() {
+    // Lines, whitespace and comment prefixes all straddle buffer boundaries
+    let builder = JsonlBuilder::new().buffer_capacity(3).comment_prefix("# ");
+    let count = JsonlReader::count(builder.build(Cursor::new(DATA.as_bytes())))
+        .await
+        .unwrap();
+    assert_eq!(count, 4);
+}
+
+#[tokio::test]
+async fn test_count_matches_stream() {
+    let builder = JsonlBuilder::new().comment_prefix("#");
+    let count = JsonlReader::count(builder.build(Cursor::new(DATA.as_bytes())))
+        .await
+        .unwrap();
+    let streamed = futures::StreamExt::count(builder.build(Cursor::new(DATA.as_bytes()))).await;
+    assert_eq!(count, streamed);
+}
+
+#[tokio::test]
+async fn test_count_matches_stream_with_byte_order_marks() {
+    // Only a byte order mark at the very start of the input is dropped
+    let data = "{\"a\":1}\n\u{FEFF}\n{\"b\":2}\n";
+    let reader = Jsonl::new(Cursor::new(data.as_bytes()))
+        .skip_n(1)
+        .await
+        .unwrap();
+    let count = JsonlReader::count(reader).await.unwrap();
+    let reader = Jsonl::new(Cursor::new(data.as_bytes()))
+        .skip_n(1)
+        .await
+        .unwrap();
+    let streamed = futures::StreamExt::count(reader).await;
+    assert_eq!((count, streamed), (2, 2));
+
+    // Even when split across buffers
+    let data = "\u{FEFF}\n{\"a\":1}\n";
+    for capacity in [1, 2, 3, 1024] {
+        let builder = JsonlBuilder::new().buffer_capacity(capacity);
+        let count = JsonlReader::count(builder.build(Cursor::new(data.as_bytes())))
+            .await
+            .unwrap();
+        let streamed = futures::StreamExt::count(builder.build(Cursor::new(data.as_bytes()))).await;
+        assert_eq!((count, streamed), (1, 1));
+    }
+}
+
+#[tokio::test]
+async fn test_count_detailed() {
+    let builder = JsonlBuilder::new().comment_prefix("#");
+    let report = builder
+        .build(Cursor::new(DATA.as_bytes()))
+        .count_detailed()
+        .await
+        .unwrap();
+
+    assert_eq!(report.total, 8);
+    assert_eq!(report.blank, 2);
+    assert_eq!(report.comments, 2);
+    assert_eq!(report.valid, 3);
+    assert_eq!(report.invalid, 1);
+}
+
+#[tokio::test]
+async fn test_count_detailed_invalid_utf8_and_long_lines() {
+    let data = b"{\"a\": \"\xff\"}\n{\"a\": \"long enough\"}\n{}\n";
+    let report = JsonlBuilder::new()
+        .max_line_length(12)
+        .build(Cursor::new(&data[..]))
+        .count_detailed()
+        .await
+        .unwrap();
+
+    assert_eq!(report.total, 3);
+    assert_eq!(report.valid, 1);
+    assert_eq!(report.invalid, 2);
+}
+
+#[tokio::test]
+async fn test_count_matches_stream_when_skipping_invalid_utf8() {
+    let data = b"{\"a\":1}\n{\"a\":\"\xff\"}\n\n{\"a\":3}\n";
+    let mut counts = Vec::new();
+    for blank_lines in [BlankLinePolicy::Skip, BlankLinePolicy::Yield] {
+        let builder = JsonlBuilder::new()
+            .utf8(Utf8Policy::Skip)
+            .blank_lines(blank_lines);
+        let count = JsonlReader::count(builder.build(Cursor::new(&data[..])))
+            .await
+            .unwrap();
+        let streamed = futures::StreamExt::count(builder.build(Cursor::new(&data[..]))).await;
+        assert_eq!(count, streamed);
+        counts.push(count);
+    }
+    assert_eq!(counts, vec![2, 3]);
+}
+
+#[tokio::test]
+async fn test_count_unicode_whitespace_matches_stream() {
+    // No-break space, vertical tab and ideographic space are whitespace to `str::trim`
+    let data = "{\"a\":1}\n\u{a0}\n\x0b\n\u{3000}# c\n\u{3000}{\"a\":2}\u{2003}\n";
+    for capacity in [1, 2, 1024] {
+        let builder = JsonlBuilder::new()
+            .buffer_capacity(capacity)
+            .comment_prefix("#");
+        let count = JsonlReader::count(builder.build(Cursor::new(data.as_bytes())))
+            .await
+            .unwrap();
+        let streamed = futures::StreamExt::count(builder.build(Cursor::new(data.as_bytes()))).await;
+        assert_eq!(count, streamed);
+        assert_eq!(count, 2);
+
+        let report = builder
+            .build(Cursor::new(data.as_bytes()))
+            .count_detailed()
+            .await
+            .unwrap();
+        assert_eq!(report.total, 5);
+        assert_eq!(report.blank, 2);
+        assert_eq!(report.comments, 1);
+        assert_eq!(report.valid, 2);
+        assert_eq!(report.invalid, 0);
+    }
+}
+
+#[tokio::test]
+async fn test_count_cut_off_character_is_a_record() {
+    let data = b"\xe3\x80\n\xe3\x80";
+    let builder = JsonlBuilder::new().buffer_capacity(1);
+    let count = JsonlReader::count(builder.build(Cursor::new(&data[..])))
+        .await
+        .unwrap();
+    let streamed = futures::StreamExt::count(builder.build(Cursor::new(&data[..]))).await;
+    assert_eq!(count, streamed);
+    assert_eq!(count, 2);
+}
@@ -0,0 +1,137 @@
+mod common;
+
+use async_jsonl::{Durability, DurableJsonlWriter, JsonlWriter, TornTailPolicy};
+use common::scratch_dir;
+use serde_json::json;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+async fn open(path: &Path, durability: Durability) -> DurableJsonlWriter {
+    JsonlWriter::open_append(path, TornTailPolicy::Refuse)
+        .await
+        .unwrap()
+        .durability(durability)
+        .await
+        .unwrap()
+}
+
+fn lines_on_disk(path: &Path) -> usize {
+    std::fs::read_to_string(path).unwrap().lines().count()
+}
+
+#[tokio::test]
+async fn test_none_and_flush_leave_syncing_to_the_caller() {
+    let dir = scratch_dir("none");
+    let path = dir.join("log.jsonl");
+
+    let log = open(&path, Durability::None).await;
+    for id in 0..3 {
+        log.append(&json!({ "id": id })).await.unwrap();
+    }
+    assert_eq!(lines_on_disk(&path), 0);
+    log.sync().await.unwrap();
+    assert_eq!((log.synced(), lines_on_disk(&path)), (3, 3));
+
+    let log = open(&path, Durability::Flush).await;
+    log.append(&json!({"id": 3})).await.unwrap();
+    assert_eq!((log.synced(), lines_on_disk(&path)), (0, 4));
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_fsync_every_n_records() {
+    let dir = scratch_dir("every_n");
+    let path = dir.join("log.jsonl");
+
+    let log = open(&path, Durability::FsyncEveryN(3)).await;
+    let mut synced = Vec::new();
+    for id in 0..7 {
+        log.append(&json!({ "id": id })).await.unwrap();
+        synced.push(log.synced());
+    }
+    assert_eq!(synced, vec![0, 0, 3, 3, 3, 6, 6]);
+
+    let writer = log.finish().await.unwrap();
+    drop(writer);
+    assert_eq!(lines_on_disk(&path), 7);
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_fsync_interval() {
+    let dir = scratch_dir("interval");
+    let path = dir.join("log.jsonl");
+
+    let log = open(&path, Durability::FsyncInterval(Duration::ZERO)).await;
+    log.append(&json!({"id": 0})).await.unwrap();
+    log.append(&json!({"id": 1})).await.unwrap();
+    assert_eq!(log.synced(), 2);
+
+    let log = open(&path, Durability::FsyncInterval(Duration::from_secs(3600))).await;
+    log.append(&json!({"id": 2})).await.unwrap();
+    assert_eq!(log.synced(), 0);
+    log.sync().await.unwrap();
+    assert_eq!(log.synced(), 1);
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+/// Append `tasks` records concurrently, returning for every record the
+/// number it has in the file, counted from 1, and `synced()` when its append
+/// returned.
+async fn append_concurrently(path: &Path, durability: Durability, tasks: u64) -> Vec<(u64, u64)> {
+    let log = Arc::new(open(path, durability).await);
+    let handles: Vec<_> = (0..tasks)
+        .map(|id| {
+            let log = log.clone();
+            tokio::spawn(async move {
+                log.append(&json!({ "id": id })).await.unwrap();
+                log.synced()
+            })
+        })
+        .collect();
+    let mut synced = Vec::new();
+    for handle in handles {
+        synced.push(handle.await.unwrap());
+    }
+    log.sync().await.unwrap();
+    assert_eq!(log.synced(), tasks);
+
+    let content = std::fs::read_to_string(path).unwrap();
+    let mut records = vec![None; synced.len()];
+    for (number, line) in content.lines().enumerate() {
+        let id = serde_json::from_str::<serde_json::Value>(line).unwrap()["id"]
+            .as_u64()
+            .unwrap() as usize;
+        assert!(records[id].is_none(), "record {} written twice", id);
+        records[id] = Some((number as u64 + 1, synced[id]));
+    }
+    // Every record was written exactly once
+    records.into_iter().map(Option::unwrap).collect()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_group_commit_returns_once_records_are_synced() {
+    let dir = scratch_dir("group");
+    let path = dir.join("log.jsonl");
+
+    let records = append_concurrently(&path, Durability::GroupCommit, 32).await;
+    for (number, synced) in records {
+        // The caller's own record was on disk when it returned
+        assert!(synced >= number, "record {} returned at {}", number, synced);
+    }
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_fsync_every_n_with_concurrent_appends() {
+    let dir = scratch_dir("every_n_concurrent");
+    let path = dir.join("log.jsonl");
+
+    // Appends overtaken by another task's sync must not sync again
+    let records = append_concurrently(&path, Durability::FsyncEveryN(1), 64).await;
+    for (number, synced) in records {
+        assert!(synced >= number, "record {} returned at {}", number, synced);
+    }
+    std::fs::remove_dir_all(dir).unwrap();
+}
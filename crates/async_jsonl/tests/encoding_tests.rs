@@ -0,0 +1,132 @@
+use async_jsonl::{
+    Encoding, Jsonl, JsonlBuilder, JsonlDeserialize, JsonlError, JsonlReader, JsonlSeekReader,
+    JsonlStreamExt, Utf8Policy,
+};
+use futures::StreamExt;
+use serde::Deserialize;
+use std::io::Cursor;
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Record {
+    name: String,
+}
+
+const TEXT: &str = "{\"name\": \"Ċ\"}\r\n{\"name\": \"਀ 🦀\"}\n";
+
+fn utf16(text: &str, big_endian: bool, bom: bool) -> Vec<u8> {
+    let bom = bom.then_some('\u{FEFF}');
+    bom.into_iter()
+        .chain(text.chars())
+        .collect::<String>()
+        .encode_utf16()
+        .flat_map(|unit| {
+            if big_endian {
+                unit.to_be_bytes()
+            } else {
+                unit.to_le_bytes()
+            }
+        })
+        .collect()
+}
+
+async fn names<S: futures::Stream<Item = Result<Record, JsonlError>>>(stream: S) -> Vec<String> {
+    stream.map(|record| record.unwrap().name).collect().await
+}
+
+#[tokio::test]
+async fn test_utf8_bom_is_stripped() {
+    let data = format!("\u{FEFF}{}", TEXT).into_bytes();
+
+    let forward = names(Jsonl::new(Cursor::new(&data)).deserialize::<Record>()).await;
+    assert_eq!(forward, vec!["Ċ", "਀ 🦀"]);
+
+    let reverse = names(Jsonl::new(Cursor::new(&data)).rev().deserialize::<Record>()).await;
+    assert_eq!(reverse, vec!["਀ 🦀", "Ċ"]);
+
+    let last = Jsonl::new(Cursor::new(&data)).last_n(2).await.unwrap();
+    assert_eq!(names(last.deserialize::<Record>()).await.len(), 2);
+
+    let report = Jsonl::new(Cursor::new(&data))
+        .count_detailed()
+        .await
+        .unwrap();
+    assert_eq!(report.valid, 2);
+
+    let lines = futures::stream::iter(data.split_inclusive(|&b| b == b'\n'))
+        .map(|line| Ok::<_, std::io::Error>(bytes::Bytes::copy_from_slice(line)));
+    assert_eq!(names(lines.jsonl().deserialize::<Record>()).await.len(), 2);
+}
+
+#[tokio::test]
+async fn test_bom_before_comment_is_not_counted() {
+    let data = "\u{FEFF}# exported\n{\"name\": \"a\"}\n";
+    let jsonl = JsonlBuilder::new()
+        .comment_prefix("#")
+        .build(Cursor::new(data.as_bytes()));
+    assert_eq!(JsonlReader::count(jsonl).await.unwrap(), 1);
+}
+
+#[tokio::test]
+async fn test_utf16_forward_and_reverse() {
+    for big_endian in [false, true] {
+        let encoding = if big_endian {
+            Encoding::Utf16Be
+        } else {
+            Encoding::Utf16Le
+        };
+        for (bom, encoding) in [(false, encoding), (true, encoding), (true, Encoding::Auto)] {
+            let data = utf16(TEXT, big_endian, bom);
+            for capacity in [3, 8192] {
+                let builder = JsonlBuilder::new()
+                    .encoding(encoding)
+                    .buffer_capacity(capacity);
+
+                let forward = builder.build(Cursor::new(&data)).deserialize::<Record>();
+                assert_eq!(names(forward).await, vec!["Ċ", "਀ 🦀"], "{:?}", encoding);
+
+                let reverse = builder.build(Cursor::new(&data)).rev();
+                let reverse = names(reverse.deserialize::<Record>()).await;
+                assert_eq!(reverse, vec!["਀ 🦀", "Ċ"], "{:?}", encoding);
+            }
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_auto_falls_back_to_utf8() {
+    let builder = JsonlBuilder::new().encoding(Encoding::Auto);
+    for data in [
+        TEXT.to_string(),
+        format!("\u{FEFF}{}", TEXT),
+        "{}".to_string(),
+    ] {
+        let forward: Vec<_> = builder.build(Cursor::new(data.as_bytes())).collect().await;
+        let mut reverse: Vec<_> = builder
+            .build(Cursor::new(data.as_bytes()))
+            .rev()
+            .collect()
+            .await;
+        reverse.reverse();
+        assert_eq!(
+            forward.into_iter().map(Result::unwrap).collect::<Vec<_>>(),
+            reverse.into_iter().map(Result::unwrap).collect::<Vec<_>>()
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_invalid_utf16_follows_utf8_policy() {
+    // A lone high surrogate in the second line
+    let mut data = utf16("{\"name\": \"a\"}\n{\"name\": \"", false, false);
+    data.extend_from_slice(&0xD800u16.to_le_bytes());
+    data.extend_from_slice(&utf16("\"}\n", false, false));
+
+    let builder = JsonlBuilder::new().encoding(Encoding::Utf16Le);
+    let results: Vec<_> = builder.build(Cursor::new(&data)).collect().await;
+    assert_eq!(results[0].as_ref().unwrap(), r#"{"name": "a"}"#);
+    assert!(matches!(results[1], Err(JsonlError::InvalidUtf8 { .. })));
+
+    let lossy = builder.utf8(Utf8Policy::Lossy);
+    let results: Vec<_> = lossy.build(Cursor::new(&data)).rev().collect().await;
+    assert_eq!(results[0].as_ref().unwrap(), "{\"name\": \"\u{FFFD}\"}");
+}
@@ -0,0 +1,111 @@
+use async_jsonl::{Jsonl, JsonlDeserialize, JsonlError, JsonlSeekReader, Location};
+use futures::StreamExt;
+use serde::Deserialize;
+use std::io::Cursor;
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct SimpleRecord {
+    value: i32,
+}
+
+#[tokio::test]
+async fn test_parse_error_location() {
+    let data = "{\"value\": 1}\n\n{\"value\": oops}\r\n{\"value\": 3}\n";
+
+    let results: Vec<_> = Jsonl::new(Cursor::new(data.as_bytes()))
+        .deserialize::<SimpleRecord>()
+        .collect()
+        .await;
+
+    assert_eq!(results.len(), 3);
+    match &results[1] {
+        Err(JsonlError::Parse {
+            location, snippet, ..
+        }) => {
+            // Blank line 2 still counts towards the line number
+            assert_eq!(
+                *location,
+                Location {
+                    line: Some(3),
+                    offset: Some(14)
+                }
+            );
+            assert_eq!(snippet, r#"{"value": oops}"#);
+        }
+        other => panic!("expected parse error, got {:?}", other),
+    }
+    assert_eq!(results[1].as_ref().unwrap_err().line(), Some(3));
+    assert_eq!(results[1].as_ref().unwrap_err().offset(), Some(14));
+    assert_eq!(results[2].as_ref().unwrap(), &SimpleRecord { value: 3 });
+}
+
+#[tokio::test]
+async fn test_invalid_utf8_error_resyncs() {
+    let data = b"{\"value\": 1}\n{\"value\": \xff}\n{\"value\": 3}\n";
+
+    let results: Vec<_> = Jsonl::new(Cursor::new(&data[..])).collect().await;
+
+    assert_eq!(results.len(), 3);
+    let error = results[1].as_ref().unwrap_err();
+    assert!(matches!(error, JsonlError::InvalidUtf8 { .. }));
+    assert_eq!(error.line(), Some(2));
+    assert_eq!(error.offset(), Some(13));
+    assert_eq!(error.snippet(), Some("{\"value\": \u{fffd}}"));
+    assert_eq!(results[2].as_ref().unwrap(), r#"{"value": 3}"#);
+}
+
+#[tokio::test]
+async fn test_snippet_is_truncated() {
+    let data = format!("{{\"value\": \"{}\"\n", "x".repeat(500));
+
+    let results: Vec<_> = Jsonl::new(Cursor::new(data.into_bytes()))
+        .deserialize::<SimpleRecord>()
+        .collect()
+        .await;
+
+    let snippet = results[0].as_ref().unwrap_err().snippet().unwrap();
+    assert!(snippet.len() < 100);
+    assert!(snippet.ends_with("..."));
+}
+
+#[tokio::test]
+async fn test_last_n_parse_error_has_offset() {
+    let data = "{\"value\": 1}\n{\"value\": oops}\n{\"value\": 3}\n";
+
+    let results: Vec<_> = Jsonl::new(Cursor::new(data.as_bytes()))
+        .last_n(3)
+        .await
+        .unwrap()
+        .deserialize::<SimpleRecord>()
+        .collect()
+        .await;
+
+    let error = results[1].as_ref().unwrap_err();
+    assert!(matches!(error, JsonlError::Parse { .. }));
+    assert_eq!(error.line(), None);
+    assert_eq!(error.offset(), Some(13));
+}
+
+#[tokio::test]
+async fn test_missing_file_is_io_error() {
+    let result = Jsonl::from_path("/nonexistent/async_jsonl/missing.jsonl").await;
+
+    assert!(matches!(result, Err(JsonlError::Io(_))));
+}
+
+#[cfg(feature = "anyhow")]
+#[tokio::test]
+async fn test_into_anyhow() {
+    use async_jsonl::AnyhowStreamExt;
+
+    let data = "{\"value\": 1}\n{\"value\": oops}\n";
+
+    let results: Vec<anyhow::Result<SimpleRecord>> = Jsonl::new(Cursor::new(data.as_bytes()))
+        .deserialize::<SimpleRecord>()
+        .into_anyhow()
+        .collect()
+        .await;
+
+    let error = results[1].as_ref().unwrap_err();
+    assert!(error.downcast_ref::<JsonlError>().is_some());
+}
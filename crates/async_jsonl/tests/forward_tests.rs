@@ -0,0 +1,124 @@
+use async_jsonl::{Jsonl, JsonlDeserialize, JsonlError, JsonlReader};
+use futures::StreamExt;
+use serde::Deserialize;
+use std::cell::Cell;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, ReadBuf};
+
+/// Reader that can neither seek nor be shared between threads, like a pipe.
+struct Pipe<'a> {
+    data: &'a [u8],
+    _not_sync: PhantomData<Cell<()>>,
+}
+
+impl<'a> Pipe<'a> {
+    fn new(data: &'a str) -> Self {
+        Self {
+            data: data.as_bytes(),
+            _not_sync: PhantomData,
+        }
+    }
+}
+
+impl AsyncRead for Pipe<'_> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.data).poll_read(cx, buf)
+    }
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct SimpleRecord {
+    value: i32,
+}
+
+const DATA: &str = "{\"value\": 1}\n{\"value\": 2}\n\n{\"value\": 3}\n{\"value\": 4}\n";
+
+#[tokio::test]
+async fn test_first_n_without_seek() {
+    let values: Vec<_> = Jsonl::new(Pipe::new(DATA))
+        .first_n(2)
+        .await
+        .unwrap()
+        .deserialize::<SimpleRecord>()
+        .map(|record| record.unwrap().value)
+        .collect()
+        .await;
+
+    assert_eq!(values, vec![1, 2]);
+}
+
+#[tokio::test]
+async fn test_count_without_seek() {
+    assert_eq!(
+        JsonlReader::count(Jsonl::new(Pipe::new(DATA)))
+            .await
+            .unwrap(),
+        4
+    );
+}
+
+#[tokio::test]
+async fn test_last_n_buffered() {
+    let lines: Vec<_> = Jsonl::new(Pipe::new(DATA))
+        .last_n_buffered(3)
+        .await
+        .unwrap()
+        .collect()
+        .await;
+
+    let lines: Vec<_> = lines.into_iter().map(Result::unwrap).collect();
+    assert_eq!(
+        lines,
+        vec!["{\"value\": 4}", "{\"value\": 3}", "{\"value\": 2}"]
+    );
+}
+
+#[tokio::test]
+async fn test_last_n_buffered_more_than_available() {
+    let values: Vec<_> = Jsonl::new(Pipe::new(DATA))
+        .last_n_buffered(10)
+        .await
+        .unwrap()
+        .deserialize::<SimpleRecord>()
+        .map(|record| record.unwrap().value)
+        .collect()
+        .await;
+
+    assert_eq!(values, vec![4, 3, 2, 1]);
+}
+
+#[tokio::test]
+async fn test_last_n_buffered_keeps_line_numbers() {
+    let data = "{\"value\": 1}\n{oops}\n{\"value\": 3}\n";
+    let results: Vec<_> = Jsonl::new(Pipe::new(data))
+        .last_n_buffered(2)
+        .await
+        .unwrap()
+        .deserialize::<SimpleRecord>()
+        .collect()
+        .await;
+
+    assert_eq!(results[0].as_ref().unwrap(), &SimpleRecord { value: 3 });
+    match &results[1] {
+        Err(e @ JsonlError::Parse { .. }) => assert_eq!(e.line(), Some(2)),
+        other => panic!("expected parse error, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_last_n_buffered_zero() {
+    let lines: Vec<_> = Jsonl::new(Pipe::new(DATA))
+        .last_n_buffered(0)
+        .await
+        .unwrap()
+        .collect()
+        .await;
+
+    assert!(lines.is_empty());
+}
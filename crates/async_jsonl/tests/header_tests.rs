@@ -0,0 +1,146 @@
+use async_jsonl::{
+    Jsonl, JsonlBuilder, JsonlDeserialize, JsonlError, JsonlSeekReader, JsonlWriter,
+};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Cursor;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Header {
+    schema_version: u32,
+    producer: String,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Sample {
+    value: u32,
+}
+
+const DATA: &str = r#"
+# exported nightly
+{"schema_version": 2, "producer": "etl"}
+{"value": 1}
+{"value": 2}
+{"value": 3}
+"#;
+
+fn header() -> Header {
+    Header {
+        schema_version: 2,
+        producer: "etl".to_string(),
+    }
+}
+
+#[tokio::test]
+async fn test_with_header_splits_header_from_records() {
+    let reader = JsonlBuilder::new()
+        .comment_prefix("#")
+        .build(Cursor::new(DATA.as_bytes()));
+    let (header, samples) = reader.with_header::<Header, Sample>().await.unwrap();
+
+    let samples: Vec<Sample> = samples
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(header, self::header());
+    assert_eq!(
+        samples.iter().map(|s| s.value).collect::<Vec<_>>(),
+        vec![1, 2, 3]
+    );
+}
+
+#[tokio::test]
+async fn test_last_n_never_yields_the_header() {
+    let mut reader = JsonlBuilder::new()
+        .comment_prefix("#")
+        .build(Cursor::new(DATA.as_bytes()));
+    assert_eq!(reader.read_header::<Header>().await.unwrap(), header());
+
+    let last: Vec<Sample> = reader
+        .last_n(10)
+        .await
+        .unwrap()
+        .deserialize::<Sample>()
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(
+        last.iter().map(|s| s.value).collect::<Vec<_>>(),
+        vec![3, 2, 1]
+    );
+}
+
+#[tokio::test]
+async fn test_rev_stops_before_the_header() {
+    let data = "{\"schema_version\": 1, \"producer\": \"a\"}\n{\"value\": 7}\n";
+    let mut reader = Jsonl::new(Cursor::new(data.as_bytes()));
+    reader.read_header::<Header>().await.unwrap();
+
+    let lines: Vec<String> = reader
+        .rev()
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(lines, vec![r#"{"value": 7}"#]);
+
+    // Without data records, nothing is left
+    let data = "{\"schema_version\": 1, \"producer\": \"a\"}\n";
+    let mut reader = Jsonl::new(Cursor::new(data.as_bytes()));
+    reader.read_header::<Header>().await.unwrap();
+    assert!(reader.rev().next().await.is_none());
+}
+
+#[tokio::test]
+async fn test_missing_or_invalid_header() {
+    let reader = Jsonl::new(Cursor::new("\n\n".as_bytes()));
+    let error = reader.with_header::<Header, Sample>().await.err().unwrap();
+    assert!(matches!(error, JsonlError::MissingHeader));
+
+    let reader = Jsonl::new(Cursor::new("{\"value\": 1}\n".as_bytes()));
+    let error = reader.with_header::<Header, Sample>().await.err().unwrap();
+    assert!(matches!(error, JsonlError::Parse { .. }));
+    assert_eq!(error.line(), Some(1));
+}
+
+#[tokio::test]
+async fn test_writer_emits_header_first() {
+    let mut writer = JsonlWriter::new(Vec::new())
+        .with_header(&header())
+        .await
+        .unwrap();
+    for value in 1..=2 {
+        writer.write(&Sample { value }).await.unwrap();
+    }
+    writer.flush().await.unwrap();
+    let output = writer.into_inner();
+
+    assert_eq!(
+        String::from_utf8(output.clone()).unwrap(),
+        "{\"schema_version\":2,\"producer\":\"etl\"}\n{\"value\":1}\n{\"value\":2}\n"
+    );
+    let (header, samples) = Jsonl::new(Cursor::new(output))
+        .with_header::<Header, Sample>()
+        .await
+        .unwrap();
+    assert_eq!(header, self::header());
+    assert_eq!(samples.count().await, 2);
+}
+
+#[tokio::test]
+async fn test_writer_reports_serialization_errors() {
+    let mut writer = JsonlWriter::new(Vec::new());
+    let mut bad = HashMap::new();
+    bad.insert((1, 2), 3);
+
+    let error = writer.write(&bad).await.unwrap_err();
+    assert!(matches!(error, JsonlError::Serialize(_)));
+    writer.write(&Sample { value: 1 }).await.unwrap();
+    assert_eq!(writer.into_inner(), b"{\"value\":1}\n");
+}
@@ -1,4 +1,4 @@
-use async_jsonl::{Jsonl, JsonlDeserialize, JsonlReader, JsonlValueDeserialize};
+use async_jsonl::{Jsonl, JsonlDeserialize, JsonlReader, JsonlSeekReader, JsonlValueDeserialize};
 use futures::StreamExt;
 use serde::Deserialize;
 use serde_json::Value;
@@ -289,7 +289,7 @@ async fn test_count_empty_file() {
     let reader = Cursor::new(data.as_bytes());
     let jsonl = Jsonl::new(reader);
 
-    let count = JsonlReader::count(jsonl).await;
+    let count = JsonlReader::count(jsonl).await.unwrap();
     assert_eq!(count, 0);
 }
 
@@ -300,7 +300,7 @@ async fn test_count_single_line() {
     let reader = Cursor::new(data.as_bytes());
     let jsonl = Jsonl::new(reader);
 
-    let count = JsonlReader::count(jsonl).await;
+    let count = JsonlReader::count(jsonl).await.unwrap();
     assert_eq!(count, 1);
 }
 
@@ -316,7 +316,7 @@ async fn test_count_multiple_lines() {
     let reader = Cursor::new(data.as_bytes());
     let jsonl = Jsonl::new(reader);
 
-    let count = JsonlReader::count(jsonl).await;
+    let count = JsonlReader::count(jsonl).await.unwrap();
     assert_eq!(count, 5);
 }
 
@@ -334,7 +334,7 @@ async fn test_count_with_empty_lines() {
     let reader = Cursor::new(data.as_bytes());
     let jsonl = Jsonl::new(reader);
 
-    let count = JsonlReader::count(jsonl).await;
+    let count = JsonlReader::count(jsonl).await.unwrap();
     // Empty lines should be filtered out, so only 3 valid lines
     assert_eq!(count, 3);
 }
@@ -350,7 +350,7 @@ async fn test_count_large_file() {
     let reader = Cursor::new(data.as_bytes());
     let jsonl = Jsonl::new(reader);
 
-    let count = JsonlReader::count(jsonl).await;
+    let count = JsonlReader::count(jsonl).await.unwrap();
     assert_eq!(count, 1000);
 }
 
@@ -368,7 +368,7 @@ another_invalid_line
     let reader = Cursor::new(data.as_bytes());
     let jsonl = Jsonl::new(reader);
 
-    let count = JsonlReader::count(jsonl).await;
+    let count = JsonlReader::count(jsonl).await.unwrap();
     assert_eq!(count, 5);
 }
 
@@ -382,7 +382,7 @@ async fn test_count_consistency_with_streaming() {
     // Count using the count method
     let reader1 = Cursor::new(data.as_bytes());
     let jsonl1 = Jsonl::new(reader1);
-    let count_method_result = JsonlReader::count(jsonl1).await;
+    let count_method_result = JsonlReader::count(jsonl1).await.unwrap();
 
     // Count by manually consuming the stream
     let reader2 = Cursor::new(data.as_bytes());
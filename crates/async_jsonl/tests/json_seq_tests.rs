@@ -0,0 +1,149 @@
+use async_jsonl::{
+    Framing, JsonlBuilder, JsonlDeserialize, JsonlError, JsonlReader, JsonlSeekReader,
+};
+use futures::StreamExt;
+use serde_json::{json, Value};
+use std::io::Cursor;
+
+const SEQ: &[u8] = b"\x1e{\"id\": 1}\n\x1e\x1e{\n  \"id\": 2\n}\n\x1e\"three\"\n";
+
+fn builder() -> JsonlBuilder {
+    JsonlBuilder::new().framing(Framing::JsonSeq)
+}
+
+#[tokio::test]
+async fn test_json_seq_records_span_lines_and_skip_empty_ones() {
+    let lines: Vec<_> = builder()
+        .build(Cursor::new(SEQ))
+        .with_positions()
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect::<Result<_, _>>()
+        .unwrap();
+
+    let records: Vec<_> = lines
+        .iter()
+        .map(|line| (line.line, line.offset, line.value.as_str()))
+        .collect();
+    assert_eq!(
+        records,
+        vec![
+            (1, 1, r#"{"id": 1}"#),
+            (2, 13, "{\n  \"id\": 2\n}"),
+            (3, 28, r#""three""#),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_json_seq_reports_truncated_records() {
+    let data = b"\x1e123\x1e{\"id\": 2}\n\x1e{\"id\": 3}\x1e{\"id\": 4}\n\x1e{\"id\": 5";
+    let results: Vec<_> = builder()
+        .build(Cursor::new(&data[..]))
+        .deserialize::<Value>()
+        .collect()
+        .await;
+
+    assert_eq!(results.len(), 5);
+    // A number cut short still parses, so it is only caught by the framing
+    assert!(
+        matches!(&results[0], Err(JsonlError::Truncated { location, .. }) if location.line == Some(1))
+    );
+    assert_eq!(results[1].as_ref().unwrap(), &json!({"id": 2}));
+    assert!(matches!(&results[2], Err(JsonlError::Truncated { .. })));
+    assert_eq!(results[3].as_ref().unwrap(), &json!({"id": 4}));
+    let error = results[4].as_ref().unwrap_err();
+    assert!(matches!(error, JsonlError::Truncated { .. }));
+    assert_eq!(error.snippet(), Some(r#"{"id": 5"#));
+}
+
+#[tokio::test]
+async fn test_json_seq_read_backwards() {
+    let lines: Vec<_> = builder()
+        .build(Cursor::new(SEQ))
+        .rev()
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(
+        lines,
+        vec![r#""three""#, "{\n  \"id\": 2\n}", r#"{"id": 1}"#]
+    );
+
+    let data = b"\x1e{\"id\": 1}\n\x1e{\"id\": 2";
+    let mut last = builder()
+        .build(Cursor::new(&data[..]))
+        .last_n(2)
+        .await
+        .unwrap();
+    let error = last.next().await.unwrap().unwrap_err();
+    assert!(matches!(error, JsonlError::Truncated { .. }));
+    assert_eq!(error.offset(), Some(12));
+    assert_eq!(last.next().await.unwrap().unwrap(), r#"{"id": 1}"#);
+}
+
+#[tokio::test]
+async fn test_json_seq_count() {
+    let data = b"\x1e{\"id\": 1}\n\x1e\n\x1e2\x1e[3]\n";
+
+    let count = JsonlReader::count(builder().build(Cursor::new(&data[..])))
+        .await
+        .unwrap();
+    assert_eq!(count, 3);
+
+    let report = builder()
+        .build(Cursor::new(&data[..]))
+        .count_detailed()
+        .await
+        .unwrap();
+    assert_eq!((report.total, report.blank), (4, 1));
+    assert_eq!((report.valid, report.invalid), (2, 1));
+}
+
+#[tokio::test]
+async fn test_json_seq_skip_and_range() {
+    let data: Vec<u8> = (0..10)
+        .flat_map(|i| format!("\x1e\x1e{{\"id\": {}}}\n", i).into_bytes())
+        .collect();
+
+    let reader = builder()
+        .build(Cursor::new(data))
+        .range(3..5)
+        .await
+        .unwrap();
+    let values: Vec<Value> = reader
+        .deserialize::<Value>()
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(values, vec![json!({"id": 3}), json!({"id": 4})]);
+}
+
+#[tokio::test]
+async fn test_json_seq_encode_round_trip() {
+    let mut buf = Vec::new();
+    Framing::JsonSeq
+        .encode(&mut buf, &json!({"id": 1}))
+        .unwrap();
+    Framing::JsonSeq.encode(&mut buf, &7).unwrap();
+    assert_eq!(buf, b"\x1e{\"id\":1}\n\x1e7\n");
+
+    let values: Vec<Value> = builder()
+        .build(Cursor::new(buf))
+        .deserialize::<Value>()
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(values, vec![json!({"id": 1}), json!(7)]);
+
+    let mut lines = Vec::new();
+    Framing::Lines.encode(&mut lines, &json!([1])).unwrap();
+    assert_eq!(lines, b"[1]\n");
+}
@@ -0,0 +1,256 @@
+use async_jsonl::{
+    JsonStream, JsonlBuilder, JsonlDeserialize, JsonlError, JsonlReader, LongLinePolicy, Utf8Policy,
+};
+use futures::StreamExt;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::io::Cursor;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, ReadBuf};
+
+const PRETTY: &str = r#"{
+  "id": 1,
+  "tags": ["a", "}"]
+}
+{
+  "id": 2,
+  "note": "escaped \" { quote"
+}{"id": 3}  [1, 2]
+"text" 42 true
+"#;
+
+/// Reader handing out a single byte per read, to split values across buffers.
+struct OneByte<'a>(&'a [u8]);
+
+impl AsyncRead for OneByte<'_> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        if let Some((&first, rest)) = self.0.split_first() {
+            buf.put_slice(&[first]);
+            self.0 = rest;
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[tokio::test]
+async fn test_json_stream_splits_pretty_and_concatenated_values() {
+    let values: Vec<Value> = JsonStream::new(Cursor::new(PRETTY.as_bytes()))
+        .deserialize::<Value>()
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect::<Result<_, _>>()
+        .unwrap();
+
+    assert_eq!(
+        values,
+        vec![
+            json!({"id": 1, "tags": ["a", "}"]}),
+            json!({"id": 2, "note": "escaped \" { quote"}),
+            json!({"id": 3}),
+            json!([1, 2]),
+            json!("text"),
+            json!(42),
+            json!(true),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_json_stream_values_split_across_reads() {
+    let values: Vec<Value> = JsonStream::new(OneByte(PRETTY.as_bytes()))
+        .deserialize::<Value>()
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect::<Result<_, _>>()
+        .unwrap();
+
+    assert_eq!(values.len(), 7);
+    assert_eq!(values[1]["note"], "escaped \" { quote");
+    assert_eq!(values[5], json!(42));
+}
+
+#[tokio::test]
+async fn test_json_stream_bom_split_across_reads() {
+    let data = "\u{FEFF}{\"a\": 1}\n";
+    let values: Vec<_> = JsonStream::new(OneByte(data.as_bytes()))
+        .with_positions()
+        .collect()
+        .await;
+    assert_eq!(values.len(), 1);
+    let value = values[0].as_ref().unwrap();
+    assert_eq!(value.value, r#"{"a": 1}"#);
+    assert_eq!((value.line, value.offset), (1, 3));
+
+    // Bytes that only start like a byte order mark are part of a value
+    let data = b"\xEF\xBB1 2";
+    let values: Vec<_> = JsonStream::new(OneByte(data))
+        .with_positions()
+        .collect()
+        .await;
+    assert_eq!(values.len(), 2);
+    assert!(matches!(values[0], Err(JsonlError::InvalidUtf8 { .. })));
+    assert_eq!(values[1].as_ref().unwrap().value, "2");
+    let values: Vec<_> = JsonStream::new(OneByte(b"\xEF")).collect().await;
+    assert!(matches!(values[..], [Err(JsonlError::InvalidUtf8 { .. })]));
+}
+
+#[tokio::test]
+async fn test_json_stream_positions() {
+    let data = "\u{feff}{\"id\": 1}\n\n  {\n  \"id\": 2\n}{\"id\": 3}";
+
+    let values: Vec<_> = JsonStream::new(Cursor::new(data.as_bytes()))
+        .with_positions()
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect::<Result<_, _>>()
+        .unwrap();
+
+    let positions: Vec<_> = values.iter().map(|v| (v.line, v.offset, v.len)).collect();
+    assert_eq!(positions, vec![(1, 3, 9), (3, 16, 13), (5, 29, 9)]);
+    assert_eq!(values[1].value, "{\n  \"id\": 2\n}");
+    assert_eq!(&data.as_bytes()[16..29], values[1].value.as_bytes());
+}
+
+#[tokio::test]
+async fn test_json_stream_reports_bad_values_and_carries_on() {
+    #[derive(Debug, Deserialize)]
+    struct Record {
+        id: u32,
+    }
+
+    let data = "{\"id\": 1}\n{\"id\": oops}\n{\"id\": 3}\n{\"id\": 4";
+    let results: Vec<_> = JsonStream::new(Cursor::new(data.as_bytes()))
+        .deserialize::<Record>()
+        .collect()
+        .await;
+
+    assert_eq!(results.len(), 4);
+    assert_eq!(results[0].as_ref().unwrap().id, 1);
+    match &results[1] {
+        Err(JsonlError::Parse { location, .. }) => {
+            assert_eq!(location.line, Some(2));
+            assert_eq!(location.offset, Some(10));
+        }
+        other => panic!("expected a parse error, got {:?}", other),
+    }
+    assert_eq!(results[2].as_ref().unwrap().id, 3);
+    // A value cut short by EOF fails to parse rather than vanishing
+    assert_eq!(results[3].as_ref().unwrap_err().line(), Some(4));
+}
+
+#[tokio::test]
+async fn test_json_stream_first_n_and_count() {
+    let first: Vec<Value> = JsonStream::new(Cursor::new(PRETTY.as_bytes()))
+        .first_n(2)
+        .await
+        .unwrap()
+        .deserialize::<Value>()
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(
+        first,
+        vec![
+            json!({"id": 1, "tags": ["a", "}"]}),
+            json!({"id": 2, "note": "escaped \" { quote"})
+        ]
+    );
+
+    let count = JsonlReader::count(JsonStream::new(Cursor::new(PRETTY.as_bytes())))
+        .await
+        .unwrap();
+    assert_eq!(count, 7);
+
+    let report = JsonStream::new(Cursor::new("{\"a\": 1} {bad} [2]".as_bytes()))
+        .count_detailed()
+        .await
+        .unwrap();
+    assert_eq!((report.total, report.valid, report.invalid), (3, 2, 1));
+}
+
+#[tokio::test]
+async fn test_json_stream_range() {
+    let values: Vec<String> = JsonStream::new(Cursor::new("1 2 3 4 5 6".as_bytes()))
+        .range(2..4)
+        .await
+        .unwrap()
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect::<Result<_, _>>()
+        .unwrap();
+
+    assert_eq!(values, vec!["3", "4"]);
+}
+
+#[tokio::test]
+async fn test_json_stream_limits_value_size_and_resyncs() {
+    let blob = "x".repeat(100);
+    let data = format!(
+        "{{\"id\": 1}} {{\"blob\": \"{}\"}} {{\"id\": 2}}\n{{\"open\": \"{}",
+        blob, blob
+    );
+    let builder = JsonlBuilder::new().max_line_length(16).buffer_capacity(4);
+    let results: Vec<_> = builder
+        .json_stream(Cursor::new(data.clone().into_bytes()))
+        .collect()
+        .await;
+
+    assert_eq!(results.len(), 4);
+    assert_eq!(results[0].as_ref().unwrap(), "{\"id\": 1}");
+    match &results[1] {
+        Err(JsonlError::LineTooLong {
+            location, limit, ..
+        }) => {
+            assert_eq!((location.offset, *limit), (Some(10), 16));
+        }
+        other => panic!("expected an oversized value, got {:?}", other),
+    }
+    assert_eq!(results[2].as_ref().unwrap(), "{\"id\": 2}");
+    // A value that never closes is dropped past the limit, not buffered
+    assert!(matches!(results[3], Err(JsonlError::LineTooLong { .. })));
+
+    let report = builder
+        .json_stream(Cursor::new(data.into_bytes()))
+        .count_detailed()
+        .await
+        .unwrap();
+    assert_eq!((report.total, report.valid, report.invalid), (4, 2, 2));
+}
+
+#[tokio::test]
+async fn test_json_stream_applies_long_value_and_utf8_policies() {
+    let data = b"\"abcdefghij\" \"\xff\" 12345678901 7";
+
+    let values: Vec<String> = JsonlBuilder::new()
+        .max_line_length(8)
+        .long_lines(LongLinePolicy::Truncate)
+        .utf8(Utf8Policy::Lossy)
+        .json_stream(Cursor::new(&data[..]))
+        .map(Result::unwrap)
+        .collect()
+        .await;
+    assert_eq!(values, vec!["\"abcdefg", "\"\u{FFFD}\"", "12345678", "7"]);
+
+    let builder = JsonlBuilder::new().utf8(Utf8Policy::Skip);
+    let values: Vec<String> = builder
+        .json_stream(Cursor::new(&data[..]))
+        .map(Result::unwrap)
+        .collect()
+        .await;
+    assert_eq!(values, vec!["\"abcdefghij\"", "12345678901", "7"]);
+    let count = JsonlReader::count(builder.json_stream(Cursor::new(&data[..])))
+        .await
+        .unwrap();
+    assert_eq!(count, 3);
+}
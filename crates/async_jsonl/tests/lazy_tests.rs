@@ -0,0 +1,117 @@
+use async_jsonl::{Jsonl, JsonlError, JsonlSeekReader, JsonlValueDeserialize};
+use futures::StreamExt;
+use serde::Deserialize;
+use std::borrow::Cow;
+use std::io::Cursor;
+
+const DATA: &str = r#"{"type": "click", "user": {"id": 42, "name": "Alice"}, "tags": ["a", "b"]}
+{"type": "view", "user": {"id": 7, "name": "Bob \"B\""}, "a/b": {"~k": 1}}
+"#;
+
+#[tokio::test]
+async fn test_deserialize_raw() {
+    let raws: Vec<_> = Jsonl::new(Cursor::new(DATA.as_bytes()))
+        .deserialize_raw()
+        .collect()
+        .await;
+
+    assert_eq!(raws.len(), 2);
+    assert_eq!(
+        raws[0].as_ref().unwrap().get(),
+        DATA.lines().next().unwrap()
+    );
+}
+
+#[tokio::test]
+async fn test_lazy_record_fields() {
+    let records: Vec<_> = Jsonl::new(Cursor::new(DATA.as_bytes()))
+        .deserialize_lazy()
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect::<Result<_, _>>()
+        .unwrap();
+
+    let first = &records[0];
+    assert_eq!(first.get_str("/type").unwrap().as_deref(), Some("click"));
+    assert!(matches!(
+        first.get_str("/type").unwrap(),
+        Some(Cow::Borrowed(_))
+    ));
+    assert_eq!(first.get_u64("/user/id").unwrap(), Some(42));
+    assert_eq!(first.get_str("/tags/1").unwrap().as_deref(), Some("b"));
+    assert_eq!(first.get_str("/tags/2").unwrap(), None);
+    assert_eq!(first.get_str("/missing/field").unwrap(), None);
+    assert_eq!(first.location().line, Some(1));
+
+    let second = &records[1];
+    assert_eq!(
+        second.get_str("/user/name").unwrap().as_deref(),
+        Some("Bob \"B\"")
+    );
+    assert_eq!(second.get_u64("/a~1b/~0k").unwrap(), Some(1));
+    assert_eq!(second.pointer("").unwrap().unwrap().get(), second.get());
+}
+
+#[tokio::test]
+async fn test_lazy_record_deserialize_field() {
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct User<'a> {
+        id: u64,
+        name: &'a str,
+    }
+
+    let record = Jsonl::new(Cursor::new(DATA.as_bytes()))
+        .deserialize_lazy()
+        .next()
+        .await
+        .unwrap()
+        .unwrap();
+
+    let user: User = record.deserialize_field("/user").unwrap().unwrap();
+    assert_eq!(
+        user,
+        User {
+            id: 42,
+            name: "Alice"
+        }
+    );
+    let tags: Vec<String> = record.deserialize_field("/tags").unwrap().unwrap();
+    assert_eq!(tags, vec!["a", "b"]);
+}
+
+#[tokio::test]
+async fn test_lazy_record_field_errors() {
+    let record = Jsonl::new(Cursor::new(DATA.as_bytes()))
+        .deserialize_lazy()
+        .next()
+        .await
+        .unwrap()
+        .unwrap();
+
+    match record.get_u64("/type") {
+        Err(JsonlError::Field {
+            location, pointer, ..
+        }) => {
+            assert_eq!(location.line, Some(1));
+            assert_eq!(pointer, "/type");
+        }
+        other => panic!("expected field error, got {:?}", other),
+    }
+    assert!(record.get_str("type").is_err());
+}
+
+#[tokio::test]
+async fn test_lazy_invalid_line() {
+    let data = "{\"a\": 1}\n{broken\n";
+    let results: Vec<_> = Jsonl::new(Cursor::new(data.as_bytes()))
+        .last_n(2)
+        .await
+        .unwrap()
+        .deserialize_lazy()
+        .collect()
+        .await;
+
+    assert!(matches!(results[0], Err(JsonlError::Parse { .. })));
+    assert_eq!(results[1].as_ref().unwrap().get_u64("/a").unwrap(), Some(1));
+}
@@ -0,0 +1,157 @@
+use async_jsonl::{
+    ErrorPolicy, Jsonl, JsonlBuilder, JsonlDeserialize, JsonlError, JsonlSeekReader, Utf8Policy,
+};
+use futures::StreamExt;
+use serde::Deserialize;
+use std::io::Cursor;
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct SimpleRecord {
+    value: i32,
+}
+
+const DATA: &str = "{\"value\": 1}\n{\"value\": oops}\n{\"value\": 3}\nnot json\n{\"value\": 5}\n";
+
+#[tokio::test]
+async fn test_fail_fast_stops_at_first_error() {
+    let results: Vec<_> = Jsonl::new(Cursor::new(DATA.as_bytes()))
+        .deserialize_with::<SimpleRecord>(ErrorPolicy::FailFast)
+        .collect()
+        .await;
+
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].as_ref().unwrap(), &SimpleRecord { value: 1 });
+    assert_eq!(results[1].as_ref().unwrap_err().line(), Some(2));
+}
+
+#[tokio::test]
+async fn test_skip_counts_bad_lines() {
+    let mut records = Jsonl::new(Cursor::new(DATA.as_bytes()))
+        .deserialize_with::<SimpleRecord>(ErrorPolicy::Skip);
+
+    let mut values = Vec::new();
+    while let Some(record) = records.next().await {
+        values.push(record.unwrap().value);
+    }
+
+    assert_eq!(values, vec![1, 3, 5]);
+    assert_eq!(records.skipped(), 2);
+}
+
+#[tokio::test]
+async fn test_collect_reports_at_end() {
+    let results: Vec<_> = Jsonl::new(Cursor::new(DATA.as_bytes()))
+        .deserialize_with::<SimpleRecord>(ErrorPolicy::Collect)
+        .collect()
+        .await;
+
+    assert_eq!(results.len(), 4);
+    assert!(results[..3].iter().all(Result::is_ok));
+    match &results[3] {
+        Err(JsonlError::Collected(errors)) => {
+            let lines: Vec<_> = errors.iter().map(JsonlError::line).collect();
+            assert_eq!(lines, vec![Some(2), Some(4)]);
+        }
+        other => panic!("expected collected errors, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_collect_without_errors_ends_cleanly() {
+    let results: Vec<_> = Jsonl::new(Cursor::new("{\"value\": 1}\n".as_bytes()))
+        .deserialize_with::<SimpleRecord>(ErrorPolicy::Collect)
+        .collect()
+        .await;
+
+    assert_eq!(results.len(), 1);
+    assert!(results[0].is_ok());
+}
+
+#[tokio::test]
+async fn test_dead_letter_receives_raw_lines() {
+    let (sender, receiver) = futures::channel::mpsc::unbounded();
+    let data = b"{\"value\": 1}\n\xff\xfe\n{\"value\": oops}\n{\"value\": 4}\n";
+    let reader = JsonlBuilder::new()
+        .utf8(Utf8Policy::Error)
+        .build(Cursor::new(&data[..]));
+
+    let mut records = reader.deserialize_with::<SimpleRecord>(ErrorPolicy::dead_letter(sender));
+    let mut values = Vec::new();
+    while let Some(record) = records.next().await {
+        values.push(record.unwrap().value);
+    }
+    assert_eq!(values, vec![1, 4]);
+    assert_eq!(records.skipped(), 2);
+    drop(records);
+
+    let letters: Vec<_> = receiver.collect().await;
+    assert_eq!(letters.len(), 2);
+    assert_eq!(letters[0].line, None);
+    assert!(matches!(letters[0].error, JsonlError::InvalidUtf8 { .. }));
+    assert_eq!(letters[1].line.as_deref(), Some("{\"value\": oops}"));
+    assert_eq!(letters[1].error.line(), Some(3));
+}
+
+#[tokio::test]
+async fn test_dead_letter_waits_for_a_bounded_sink() {
+    let (sender, receiver) = futures::channel::mpsc::channel(0);
+    let data = "oops 1\n{\"value\": 1}\noops 2\noops 3\noops 4\n{\"value\": 2}\n";
+    let letters = tokio::spawn(async move {
+        let mut letters = Vec::new();
+        let mut receiver = receiver;
+        while let Some(letter) = receiver.next().await {
+            letters.push(letter);
+            // A slow consumer keeps the sink full while lines keep failing
+            tokio::task::yield_now().await;
+        }
+        letters
+    });
+
+    let mut records = Jsonl::new(Cursor::new(data.as_bytes()))
+        .deserialize_with::<SimpleRecord>(ErrorPolicy::dead_letter(sender));
+    let mut values = Vec::new();
+    while let Some(record) = records.next().await {
+        values.push(record.unwrap().value);
+    }
+    assert_eq!(values, vec![1, 2]);
+    assert_eq!(records.skipped(), 4);
+    drop(records);
+
+    let lines: Vec<_> = letters
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|letter| letter.line.unwrap())
+        .collect();
+    assert_eq!(lines, vec!["oops 1", "oops 2", "oops 3", "oops 4"]);
+}
+
+#[tokio::test]
+async fn test_dead_letter_sink_failure_ends_stream() {
+    let (sender, receiver) = futures::channel::mpsc::unbounded();
+    drop(receiver);
+
+    let results: Vec<_> = Jsonl::new(Cursor::new(DATA.as_bytes()))
+        .deserialize_with::<SimpleRecord>(ErrorPolicy::dead_letter(sender))
+        .collect()
+        .await;
+
+    assert_eq!(results.len(), 2);
+    assert!(matches!(results[1], Err(JsonlError::DeadLetter(_))));
+}
+
+#[tokio::test]
+async fn test_policy_on_last_n() {
+    let mut records = Jsonl::new(Cursor::new(DATA.as_bytes()))
+        .last_n(3)
+        .await
+        .unwrap()
+        .deserialize_with::<SimpleRecord>(ErrorPolicy::Skip);
+
+    let mut values = Vec::new();
+    while let Some(record) = records.next().await {
+        values.push(record.unwrap().value);
+    }
+    assert_eq!(values, vec![5, 3]);
+    assert_eq!(records.skipped(), 1);
+}
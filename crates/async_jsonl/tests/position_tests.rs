@@ -0,0 +1,58 @@
+use async_jsonl::{Jsonl, JsonlError, Positioned};
+use futures::StreamExt;
+use serde::Deserialize;
+use std::io::Cursor;
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct SimpleRecord {
+    value: i32,
+}
+
+#[tokio::test]
+async fn test_with_positions_counts_blank_lines() {
+    let data = "{\"value\": 1}\n\n  \n{\"value\": 2}\r\n{\"value\": 3}";
+
+    let lines: Vec<Positioned<String>> = Jsonl::new(Cursor::new(data.as_bytes()))
+        .with_positions()
+        .map(|r| r.unwrap())
+        .collect()
+        .await;
+
+    assert_eq!(lines.len(), 3);
+    assert_eq!(
+        lines[0],
+        Positioned {
+            line: 1,
+            offset: 0,
+            len: 13,
+            value: r#"{"value": 1}"#.to_string()
+        }
+    );
+    assert_eq!((lines[1].line, lines[1].offset, lines[1].len), (4, 17, 14));
+    // The last line has no terminator
+    assert_eq!((lines[2].line, lines[2].offset, lines[2].len), (5, 31, 12));
+    assert_eq!(lines[2].end(), data.len() as u64);
+}
+
+#[tokio::test]
+async fn test_deserialize_positioned() {
+    let data = "{\"value\": 1}\n{\"value\": nope}\n\n{\"value\": 3}\n";
+
+    let results: Vec<_> = Jsonl::new(Cursor::new(data.as_bytes()))
+        .deserialize_positioned::<SimpleRecord>()
+        .collect()
+        .await;
+
+    assert_eq!(results.len(), 3);
+    let first = results[0].as_ref().unwrap();
+    assert_eq!(first.value, SimpleRecord { value: 1 });
+    assert_eq!((first.line, first.offset), (1, 0));
+
+    let error = results[1].as_ref().unwrap_err();
+    assert!(matches!(error, JsonlError::Parse { .. }));
+    assert_eq!((error.line(), error.offset()), (Some(2), Some(13)));
+
+    let third = results[2].as_ref().unwrap();
+    assert_eq!(third.value, SimpleRecord { value: 3 });
+    assert_eq!((third.line, third.offset, third.len), (4, 30, 13));
+}
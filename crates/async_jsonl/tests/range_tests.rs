@@ -0,0 +1,143 @@
+mod common;
+
+use async_jsonl::{Jsonl, JsonlBuilder, JsonlDeserialize, JsonlReader};
+use common::Failing;
+use futures::StreamExt;
+use serde::Deserialize;
+use std::io::Cursor;
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Record {
+    id: u32,
+}
+
+fn numbered(lines: u32) -> String {
+    (0..lines).map(|i| format!("{{\"id\": {}}}\n", i)).collect()
+}
+
+async fn ids<S: futures::Stream<Item = Result<Record, async_jsonl::JsonlError>>>(
+    stream: S,
+) -> Vec<u32> {
+    stream.map(|record| record.unwrap().id).collect().await
+}
+
+#[tokio::test]
+async fn test_skip_n_keeps_line_numbers() {
+    let data = numbered(1000);
+    let reader = JsonlBuilder::new()
+        .buffer_capacity(16)
+        .build(Cursor::new(data.as_bytes()))
+        .skip_n(997)
+        .await
+        .unwrap();
+
+    let mut lines = reader.with_positions();
+    let first = lines.next().await.unwrap().unwrap();
+    assert_eq!(first.value, r#"{"id": 997}"#);
+    assert_eq!(first.line, 998);
+    assert_eq!(first.offset, data.find(r#"{"id": 997}"#).unwrap() as u64);
+    assert_eq!(lines.count().await, 2);
+}
+
+#[tokio::test]
+async fn test_skip_n_past_end() {
+    let data = numbered(3);
+    let reader = Jsonl::new(Cursor::new(data.as_bytes()))
+        .skip_n(10)
+        .await
+        .unwrap();
+    assert_eq!(JsonlReader::count(reader).await.unwrap(), 0);
+}
+
+#[tokio::test]
+async fn test_range_deserialize() {
+    let data = numbered(100);
+    let reader = Jsonl::new(Cursor::new(data.as_bytes()))
+        .range(40..45)
+        .await
+        .unwrap();
+    assert_eq!(
+        ids(reader.deserialize::<Record>()).await,
+        vec![40, 41, 42, 43, 44]
+    );
+
+    let reader = Jsonl::new(Cursor::new(data.as_bytes()))
+        .range(98..200)
+        .await
+        .unwrap();
+    assert_eq!(ids(reader.deserialize::<Record>()).await, vec![98, 99]);
+}
+
+#[tokio::test]
+async fn test_range_counts_blank_and_comment_lines() {
+    let data = "# header\n{\"id\": 1}\n\n{\"id\": 2}\n{\"id\": 3}\n";
+    let builder = JsonlBuilder::new().comment_prefix("#");
+
+    let reader = builder
+        .build(Cursor::new(data.as_bytes()))
+        .range(1..4)
+        .await
+        .unwrap();
+    assert_eq!(ids(reader.deserialize::<Record>()).await, vec![1, 2]);
+
+    let reader = builder
+        .build(Cursor::new(data.as_bytes()))
+        .range(1..4)
+        .await
+        .unwrap();
+    assert_eq!(JsonlReader::count(reader).await.unwrap(), 2);
+}
+
+#[tokio::test]
+async fn test_range_is_relative_to_position() {
+    let data = numbered(20);
+    let reader = Jsonl::new(Cursor::new(data.as_bytes()))
+        .skip_n(10)
+        .await
+        .unwrap()
+        .range(2..4)
+        .await
+        .unwrap();
+    assert_eq!(ids(reader.deserialize::<Record>()).await, vec![12, 13]);
+}
+
+#[tokio::test]
+async fn test_nth() {
+    let data = numbered(5);
+    let reader = Jsonl::new(Cursor::new(data.as_bytes()))
+        .nth(3)
+        .await
+        .unwrap();
+    assert_eq!(ids(reader.deserialize::<Record>()).await, vec![3]);
+
+    let reader = Jsonl::new(Cursor::new(data.as_bytes()))
+        .nth(5)
+        .await
+        .unwrap();
+    assert!(ids(reader.deserialize::<Record>()).await.is_empty());
+}
+
+#[tokio::test]
+async fn test_skip_n_after_range_stops_at_range_end() {
+    let data = numbered(10);
+    let reader = Jsonl::new(Cursor::new(data.as_bytes()))
+        .range(2..5)
+        .await
+        .unwrap()
+        .skip_n(1)
+        .await
+        .unwrap();
+    assert_eq!(ids(reader.deserialize::<Record>()).await, vec![3, 4]);
+
+    // Skipping never reads past the end of the range
+    let reader = JsonlBuilder::new()
+        .buffer_capacity(16)
+        .build(Failing(numbered(3).into_bytes()))
+        .range(0..2)
+        .await
+        .unwrap()
+        .skip_n(5)
+        .await
+        .unwrap();
+    assert!(ids(reader.deserialize::<Record>()).await.is_empty());
+}
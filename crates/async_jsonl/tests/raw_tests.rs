@@ -0,0 +1,124 @@
+use async_jsonl::{Jsonl, JsonlBuilder, JsonlError, JsonlReader, LineTerminator};
+use futures::StreamExt;
+use std::io::Cursor;
+
+const DATA: &[u8] = b"\xEF\xBB\xBF{\"a\": 1}  \r\n\n  # note\n{\"b\": \"\xFF\"}\r\n\t{\"c\": 3}";
+
+#[tokio::test]
+async fn test_raw_lines_round_trip() {
+    for capacity in [1, 4, 8192] {
+        let lines: Vec<_> = JsonlBuilder::new()
+            .buffer_capacity(capacity)
+            .comment_prefix("#")
+            .build(Cursor::new(DATA))
+            .raw_lines()
+            .collect()
+            .await;
+
+        let mut copy = Vec::new();
+        for line in &lines {
+            let line = &line.as_ref().unwrap().value;
+            copy.extend_from_slice(&line.content);
+            copy.extend_from_slice(line.terminator.as_bytes());
+        }
+        assert_eq!(copy, DATA);
+        assert_eq!(lines.len(), 5);
+    }
+}
+
+#[tokio::test]
+async fn test_raw_lines_terminators_and_positions() {
+    let lines: Vec<_> = Jsonl::new(Cursor::new(DATA))
+        .raw_lines()
+        .map(Result::unwrap)
+        .collect()
+        .await;
+
+    let terminators: Vec<_> = lines.iter().map(|line| line.value.terminator).collect();
+    assert_eq!(
+        terminators,
+        vec![
+            LineTerminator::CrLf,
+            LineTerminator::Lf,
+            LineTerminator::Lf,
+            LineTerminator::CrLf,
+            LineTerminator::None,
+        ]
+    );
+    assert_eq!(&lines[0].value.content[..], b"\xEF\xBB\xBF{\"a\": 1}  ");
+    assert_eq!(&lines[1].value.content[..], b"");
+    for line in &lines {
+        let end = (line.offset + line.len) as usize;
+        let stored = &DATA[line.offset as usize..end];
+        assert!(stored.starts_with(&line.value.content));
+    }
+    assert_eq!(lines[4].line, 5);
+    assert_eq!(lines[4].end(), DATA.len() as u64);
+}
+
+#[tokio::test]
+async fn test_raw_lines_reject_overlong_lines() {
+    let data = "{\"a\": 1}\n{\"long\": \"xxxxxxxxxxxxxxxx\"}\n{\"b\": 2}\n";
+    let results: Vec<_> = JsonlBuilder::new()
+        .max_line_length(10)
+        .long_lines(async_jsonl::LongLinePolicy::Truncate)
+        .build(Cursor::new(data.as_bytes()))
+        .raw_lines()
+        .collect()
+        .await;
+
+    assert_eq!(results.len(), 3);
+    assert!(matches!(results[1], Err(JsonlError::LineTooLong { .. })));
+    assert_eq!(
+        &results[2].as_ref().unwrap().value.content[..],
+        b"{\"b\": 2}"
+    );
+}
+
+#[tokio::test]
+async fn test_raw_lines_after_skip() {
+    let lines: Vec<_> = Jsonl::new(Cursor::new(DATA))
+        .skip_n(3)
+        .await
+        .unwrap()
+        .raw_lines()
+        .map(Result::unwrap)
+        .collect()
+        .await;
+
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0].line, 4);
+    assert_eq!(&lines[0].value.content[..], b"{\"b\": \"\xFF\"}");
+}
+
+#[tokio::test]
+async fn test_raw_lines_length_limit_boundaries() {
+    let cases: [(&[u8], bool); 8] = [
+        (b"abcd", true),
+        (b"abcd\n", true),
+        (b"abcd\r\n", true),
+        (b"abcde", false),
+        (b"abcde\n", false),
+        (b"abcde\r\n", false),
+        (b"abcdef", false),
+        (b"abcdef\n", false),
+    ];
+    for (data, ok) in cases {
+        let results: Vec<_> = JsonlBuilder::new()
+            .max_line_length(4)
+            .build(Cursor::new(data))
+            .raw_lines()
+            .collect()
+            .await;
+
+        assert_eq!(results.len(), 1);
+        match ok {
+            true => assert_eq!(&results[0].as_ref().unwrap().value.content[..], b"abcd"),
+            false => assert!(
+                matches!(results[0], Err(JsonlError::LineTooLong { .. })),
+                "{:?} was not rejected",
+                String::from_utf8_lossy(data)
+            ),
+        }
+    }
+}
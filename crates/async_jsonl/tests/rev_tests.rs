@@ -0,0 +1,123 @@
+use async_jsonl::{Jsonl, JsonlBuilder, JsonlDeserialize, JsonlError, LongLinePolicy};
+use futures::StreamExt;
+use serde::Deserialize;
+use std::io::Cursor;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, AsyncSeek, ReadBuf};
+
+#[tokio::test]
+async fn test_rev_yields_every_line_last_first() {
+    let data = "{\"id\": 1}\r\n\n{\"id\": 2}\n{\"id\": 3}";
+
+    let lines: Vec<_> = Jsonl::new(Cursor::new(data.as_bytes()))
+        .rev()
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect::<Result<_, _>>()
+        .unwrap();
+
+    assert_eq!(lines, vec![r#"{"id": 3}"#, r#"{"id": 2}"#, r#"{"id": 1}"#]);
+}
+
+#[tokio::test]
+async fn test_rev_deserialize_with_offsets() {
+    #[derive(Debug, Deserialize)]
+    struct Record {
+        id: u32,
+    }
+
+    let data = "{\"id\": 1}\nbroken\n{\"id\": 3}\n";
+    let results: Vec<_> = Jsonl::new(Cursor::new(data.as_bytes()))
+        .rev()
+        .deserialize::<Record>()
+        .collect()
+        .await;
+
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[0].as_ref().unwrap().id, 3);
+    let error = results[1].as_ref().unwrap_err();
+    assert!(matches!(error, JsonlError::Parse { .. }));
+    assert_eq!(error.location().unwrap().offset, Some(10));
+    assert_eq!(error.line(), None);
+    assert_eq!(results[2].as_ref().unwrap().id, 1);
+}
+
+/// Cursor that records how far back it was read.
+struct Tracked {
+    inner: Cursor<Vec<u8>>,
+    lowest: u64,
+}
+
+impl AsyncRead for Tracked {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        let position = self.inner.position();
+        self.lowest = self.lowest.min(position);
+        Pin::new(&mut self.inner).poll_read(cx, buf)
+    }
+}
+
+impl AsyncSeek for Tracked {
+    fn start_seek(mut self: Pin<&mut Self>, position: std::io::SeekFrom) -> std::io::Result<()> {
+        Pin::new(&mut self.inner).start_seek(position)
+    }
+
+    fn poll_complete(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<u64>> {
+        Pin::new(&mut self.inner).poll_complete(cx)
+    }
+}
+
+#[tokio::test]
+async fn test_rev_reads_lazily() {
+    let data: String = (0..10_000)
+        .map(|i| format!("{{\"id\": {}}}\n", i))
+        .collect();
+    let size = data.len() as u64;
+    let reader = Tracked {
+        inner: Cursor::new(data.into_bytes()),
+        lowest: u64::MAX,
+    };
+
+    let mut lines = JsonlBuilder::new().buffer_capacity(256).build(reader).rev();
+    for i in (9_950..10_000).rev() {
+        assert_eq!(
+            lines.next().await.unwrap().unwrap(),
+            format!("{{\"id\": {}}}", i)
+        );
+    }
+
+    let lowest = lines.into_inner().lowest;
+    assert!(size - lowest < 1024, "read back {} bytes", size - lowest);
+}
+
+#[tokio::test]
+async fn test_rev_bounds_long_lines() {
+    let long = format!("{{\"data\": \"{}\"}}", "x".repeat(10_000));
+    let data = format!("{}\n{{\"a\": 1}}\n{}\n", long, long);
+
+    let builder = JsonlBuilder::new().buffer_capacity(64).max_line_length(32);
+    let results: Vec<_> = builder
+        .build(Cursor::new(data.as_bytes()))
+        .rev()
+        .collect()
+        .await;
+    assert_eq!(results.len(), 3);
+    assert!(matches!(results[0], Err(JsonlError::LineTooLong { .. })));
+    assert_eq!(results[1].as_ref().unwrap(), r#"{"a": 1}"#);
+    assert!(matches!(results[2], Err(JsonlError::LineTooLong { .. })));
+
+    let truncating = builder.long_lines(LongLinePolicy::Truncate);
+    let first = truncating
+        .build(Cursor::new(data.as_bytes()))
+        .rev()
+        .next()
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(first, long[..32]);
+}
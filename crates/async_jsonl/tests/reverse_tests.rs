@@ -1,4 +1,4 @@
-use async_jsonl::{Jsonl, JsonlReader};
+use async_jsonl::{Jsonl, JsonlReader, JsonlSeekReader};
 use futures::StreamExt;
 use std::io::Cursor;
 use tokio::fs::File;
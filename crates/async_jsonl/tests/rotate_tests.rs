@@ -0,0 +1,232 @@
+mod common;
+
+use async_jsonl::{Jsonl, JsonlDeserialize, Period, RotatingJsonlWriter};
+use common::scratch_dir;
+use futures::StreamExt;
+use serde_json::{json, Value};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// 2026-10-16T10:00:00Z
+const TEN_AM: u64 = 1_792_144_800;
+
+fn ten_am() -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(TEN_AM)
+}
+
+async fn segment_names(path: &Path) -> Vec<String> {
+    RotatingJsonlWriter::segments(path)
+        .await
+        .unwrap()
+        .iter()
+        .map(|segment| segment.file_name().unwrap().to_string_lossy().into_owned())
+        .collect()
+}
+
+async fn read_ids(path: &Path) -> Vec<u64> {
+    Jsonl::from_segments(path)
+        .await
+        .unwrap()
+        .deserialize::<Value>()
+        .map(|record| record.unwrap()["id"].as_u64().unwrap())
+        .collect()
+        .await
+}
+
+#[tokio::test]
+async fn test_rolls_over_by_size_and_reads_back_in_order() {
+    let dir = scratch_dir("size");
+    let path = dir.join("events.jsonl");
+
+    // Every record is 9 bytes, so two fit in 20
+    let mut writer = RotatingJsonlWriter::new(&path)
+        .unwrap()
+        .max_size(20)
+        .clock(ten_am);
+    for id in 0..5 {
+        writer.write(&json!({ "id": id })).await.unwrap();
+    }
+    writer.finish().await.unwrap();
+
+    assert_eq!(
+        segment_names(&path).await,
+        vec![
+            "events.2026-10-16T10.jsonl",
+            "events.2026-10-16T10.1.jsonl",
+            "events.2026-10-16T10.2.jsonl",
+        ]
+    );
+    for segment in RotatingJsonlWriter::segments(&path).await.unwrap() {
+        assert!(std::fs::metadata(segment).unwrap().len() <= 20);
+    }
+    assert_eq!(read_ids(&path).await, vec![0, 1, 2, 3, 4]);
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_rolls_over_at_period_boundaries() {
+    static NOW: AtomicU64 = AtomicU64::new(TEN_AM + 3_599);
+    fn clock() -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(NOW.load(Ordering::SeqCst))
+    }
+
+    let dir = scratch_dir("period");
+    let path = dir.join("events.jsonl");
+    let mut writer = RotatingJsonlWriter::new(&path)
+        .unwrap()
+        .period(Period::Hour)
+        .clock(clock);
+
+    writer.write(&json!({"id": 0})).await.unwrap();
+    NOW.store(TEN_AM + 3_600, Ordering::SeqCst);
+    writer.write(&json!({"id": 1})).await.unwrap();
+    writer.write(&json!({"id": 2})).await.unwrap();
+    // Into the next day, past the end of the month
+    NOW.store(TEN_AM + 15 * 86_400, Ordering::SeqCst);
+    writer.write(&json!({"id": 3})).await.unwrap();
+    writer.finish().await.unwrap();
+
+    assert_eq!(
+        segment_names(&path).await,
+        vec![
+            "events.2026-10-16T10.jsonl",
+            "events.2026-10-16T11.jsonl",
+            "events.2026-10-31T10.jsonl",
+        ]
+    );
+    assert_eq!(read_ids(&path).await, vec![0, 1, 2, 3]);
+
+    // Later runs start segments of their own
+    let mut writer = RotatingJsonlWriter::new(&path)
+        .unwrap()
+        .period(Period::Hour)
+        .clock(clock);
+    writer.write(&json!({"id": 4})).await.unwrap();
+    writer.finish().await.unwrap();
+    assert_eq!(
+        segment_names(&path).await.last().unwrap(),
+        "events.2026-10-31T10.1.jsonl"
+    );
+    assert_eq!(read_ids(&path).await, vec![0, 1, 2, 3, 4]);
+
+    let daily = dir.join("daily.jsonl");
+    let mut writer = RotatingJsonlWriter::new(&daily)
+        .unwrap()
+        .period(Period::Day)
+        .clock(clock);
+    writer.write(&json!({"id": 5})).await.unwrap();
+    writer.finish().await.unwrap();
+    assert_eq!(segment_names(&daily).await, vec!["daily.2026-10-31.jsonl"]);
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_retention_by_count_and_age() {
+    let dir = scratch_dir("retention");
+    let path = dir.join("events.jsonl");
+
+    let mut writer = RotatingJsonlWriter::new(&path)
+        .unwrap()
+        .max_records(2)
+        .keep_segments(2)
+        .clock(ten_am);
+    for id in 0..7 {
+        writer.write(&json!({ "id": id })).await.unwrap();
+    }
+    writer.finish().await.unwrap();
+    assert_eq!(read_ids(&path).await, vec![4, 5, 6]);
+
+    // Every closed segment is older than no time at all
+    let mut writer = RotatingJsonlWriter::new(&path)
+        .unwrap()
+        .max_age(Duration::ZERO)
+        .clock(ten_am);
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    writer.write(&json!({"id": 7})).await.unwrap();
+    writer.finish().await.unwrap();
+    assert_eq!(read_ids(&path).await, vec![7]);
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_failed_prune_leaves_no_segment_behind() {
+    let dir = scratch_dir("failed_prune");
+    let path = dir.join("events.jsonl");
+    // A directory in place of an old segment can't be removed as a file
+    let blocker = dir.join("events.2026-10-16T09.jsonl");
+    std::fs::create_dir(&blocker).unwrap();
+
+    let mut writer = RotatingJsonlWriter::new(&path)
+        .unwrap()
+        .keep_segments(1)
+        .clock(ten_am);
+    assert!(writer.write(&json!({"id": 0})).await.is_err());
+    assert_eq!(
+        segment_names(&path).await,
+        vec!["events.2026-10-16T09.jsonl"]
+    );
+
+    std::fs::remove_dir(&blocker).unwrap();
+    writer.write(&json!({"id": 1})).await.unwrap();
+    writer.finish().await.unwrap();
+    assert_eq!(
+        segment_names(&path).await,
+        vec!["events.2026-10-16T10.jsonl"]
+    );
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_reader_keeps_records_of_torn_segments_apart() {
+    let dir = scratch_dir("reader");
+    let path = dir.join("events.jsonl");
+    std::fs::write(dir.join("events.2026-10-16T10.jsonl"), "{\"id\": 0}").unwrap();
+    std::fs::write(dir.join("events.2026-10-16T10.1.jsonl"), "").unwrap();
+    std::fs::write(dir.join("events.2026-10-16T10.2.jsonl"), "{\"id\": 1}\n").unwrap();
+    // Neither segments of this base path nor segments at all
+    std::fs::write(dir.join("events.jsonl"), "{\"id\": 9}\n").unwrap();
+    std::fs::write(dir.join("events.archive.jsonl"), "{\"id\": 9}\n").unwrap();
+    std::fs::write(dir.join("other.2026-10-16T10.jsonl"), "{\"id\": 9}\n").unwrap();
+
+    assert_eq!(read_ids(&path).await, vec![0, 1]);
+    assert!(
+        Jsonl::from_segments(dir.join("missing").join("events.jsonl"))
+            .await
+            .is_err()
+    );
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[cfg(feature = "gzip")]
+#[tokio::test]
+async fn test_closed_segments_are_compressed() {
+    let dir = scratch_dir("gzip");
+    let path = dir.join("events.jsonl");
+
+    let mut writer = RotatingJsonlWriter::new(&path)
+        .unwrap()
+        .max_records(2)
+        .compress(true)
+        .clock(ten_am);
+    for id in 0..5 {
+        writer.write(&json!({ "id": id })).await.unwrap();
+    }
+    writer.flush().await.unwrap();
+    assert_eq!(
+        segment_names(&path).await,
+        vec![
+            "events.2026-10-16T10.jsonl.gz",
+            "events.2026-10-16T10.1.jsonl.gz",
+            "events.2026-10-16T10.2.jsonl",
+        ]
+    );
+    writer.finish().await.unwrap();
+
+    assert!(segment_names(&path)
+        .await
+        .iter()
+        .all(|name| name.ends_with(".jsonl.gz")));
+    assert_eq!(read_ids(&path).await, vec![0, 1, 2, 3, 4]);
+    std::fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,159 @@
+use async_jsonl::{Jsonl, JsonlDeserialize, JsonlError, JsonlReader, JsonlSeekReader};
+use futures::StreamExt;
+use serde::de::{DeserializeSeed, Deserializer, MapAccess, Visitor};
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::fmt;
+use std::io::Cursor;
+use std::rc::Rc;
+
+type Fields = Vec<(Rc<str>, Rc<str>)>;
+
+/// Reads an object of strings, sharing one allocation per distinct string
+#[derive(Clone, Copy)]
+struct Interned<'a>(&'a RefCell<HashSet<Rc<str>>>);
+
+impl<'de> DeserializeSeed<'de> for Interned<'_> {
+    type Value = Fields;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Fields, D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de> Visitor<'de> for Interned<'_> {
+    type Value = Fields;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an object of strings")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Fields, A::Error> {
+        let mut strings = self.0.borrow_mut();
+        let mut intern = |s: String| match strings.get(s.as_str()) {
+            Some(interned) => interned.clone(),
+            None => {
+                let interned: Rc<str> = s.into();
+                strings.insert(interned.clone());
+                interned
+            }
+        };
+        let mut fields = Vec::new();
+        while let Some((key, value)) = map.next_entry::<String, String>()? {
+            fields.push((intern(key), intern(value)));
+        }
+        Ok(fields)
+    }
+}
+
+/// Counts the lines it was handed, without being `Clone`
+struct Numbered(usize);
+
+impl<'de> DeserializeSeed<'de> for Numbered {
+    type Value = (usize, String);
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        let name = <String as serde::Deserialize>::deserialize(deserializer)?;
+        Ok((self.0, name))
+    }
+}
+
+const TELEMETRY: &str = r#"{"level": "info", "service": "api"}
+{"level": "warn", "service": "api"}
+
+{"level": "info", "service": "db"}
+"#;
+
+fn reader(data: &str) -> Jsonl<Cursor<Vec<u8>>> {
+    Jsonl::new(Cursor::new(data.as_bytes().to_vec()))
+}
+
+#[tokio::test]
+async fn test_seeded_records_share_interned_strings() {
+    let strings = RefCell::new(HashSet::new());
+    let records: Vec<Fields> = reader(TELEMETRY)
+        .deserialize_seeded(Interned(&strings))
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect::<Result<_, _>>()
+        .unwrap();
+
+    assert_eq!(records.len(), 3);
+    assert_eq!(&*records[2][1].1, "db");
+    // Keys and repeated values point at the same allocation
+    assert!(Rc::ptr_eq(&records[0][0].0, &records[2][0].0));
+    assert!(Rc::ptr_eq(&records[0][1].1, &records[1][1].1));
+    assert!(Rc::ptr_eq(&records[0][0].1, &records[2][0].1));
+    assert_eq!(strings.borrow().len(), 6);
+}
+
+#[tokio::test]
+async fn test_seed_factory_runs_once_per_line() {
+    let mut calls = 0;
+    let records: Vec<(usize, String)> = reader("\"a\"\n\"b\"\n\n\"c\"\n")
+        .deserialize_seeded_by(|| {
+            calls += 1;
+            Numbered(calls)
+        })
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect::<Result<_, _>>()
+        .unwrap();
+
+    assert_eq!(
+        records,
+        vec![
+            (1, "a".to_string()),
+            (2, "b".to_string()),
+            (3, "c".to_string())
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_seeded_errors_carry_the_line_location() {
+    let strings = RefCell::new(HashSet::new());
+    let data =
+        "{\"level\": \"info\"}\n{\"level\": 3}\n{\"level\": \"info\"} {}\n{\"level\": \"warn\"}\n";
+    let results: Vec<_> = reader(data)
+        .deserialize_seeded(Interned(&strings))
+        .collect()
+        .await;
+
+    assert_eq!(results.len(), 4);
+    assert!(results[0].is_ok() && results[3].is_ok());
+    // A value the seed rejects, then trailing characters after the record
+    for index in [1, 2] {
+        let error = results[index].as_ref().unwrap_err();
+        assert!(matches!(error, JsonlError::Parse { .. }), "{:?}", error);
+        assert_eq!(error.line(), Some(index as u64 + 1));
+    }
+}
+
+#[tokio::test]
+async fn test_seeded_works_on_partial_and_reverse_readers() {
+    let strings = RefCell::new(HashSet::new());
+
+    let first: Vec<_> = reader(TELEMETRY)
+        .first_n(1)
+        .await
+        .unwrap()
+        .deserialize_seeded(Interned(&strings))
+        .collect()
+        .await;
+    assert_eq!(first.len(), 1);
+
+    let last: Vec<_> = reader(TELEMETRY)
+        .last_n(2)
+        .await
+        .unwrap()
+        .deserialize_seeded(Interned(&strings))
+        .collect()
+        .await;
+    assert_eq!(last.len(), 2);
+    let service = &last[0].as_ref().unwrap()[1].1;
+    assert_eq!(&**service, "db");
+    assert!(strings.borrow().contains(service));
+}
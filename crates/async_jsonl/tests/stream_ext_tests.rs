@@ -0,0 +1,159 @@
+use async_jsonl::{
+    ErrorPolicy, JsonlBufReadExt, JsonlBuilder, JsonlDeserialize, JsonlError, JsonlStreamExt,
+    JsonlValueDeserialize,
+};
+use bytes::Bytes;
+use futures::{stream, StreamExt};
+use serde::Deserialize;
+use std::io::Cursor;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::io::{AsyncBufRead, AsyncRead, BufReader, ReadBuf};
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct SimpleRecord {
+    value: i32,
+}
+
+fn lines(lines: &[&str]) -> impl futures::Stream<Item = std::io::Result<String>> + Unpin {
+    stream::iter(
+        lines
+            .iter()
+            .map(|line| Ok(line.to_string()))
+            .collect::<Vec<_>>(),
+    )
+}
+
+#[tokio::test]
+async fn test_string_stream_deserialize() {
+    let values: Vec<_> = lines(&["{\"value\": 1}\n", "", "  {\"value\": 2}\r\n"])
+        .jsonl()
+        .deserialize::<SimpleRecord>()
+        .map(|record| record.unwrap().value)
+        .collect()
+        .await;
+
+    assert_eq!(values, vec![1, 2]);
+}
+
+#[tokio::test]
+async fn test_bytes_stream_errors_carry_item_number() {
+    let items = vec![
+        Ok::<_, std::io::Error>(Bytes::from_static(b"{\"value\": 1}")),
+        Ok(Bytes::from_static(b"{\"value\": \xff}")),
+        Ok(Bytes::from_static(b"{\"value\": x}")),
+    ];
+    let results: Vec<_> = stream::iter(items)
+        .jsonl()
+        .deserialize::<SimpleRecord>()
+        .collect()
+        .await;
+
+    assert_eq!(results[0].as_ref().unwrap(), &SimpleRecord { value: 1 });
+    match &results[1] {
+        Err(e @ JsonlError::InvalidUtf8 { .. }) => assert_eq!(e.line(), Some(2)),
+        other => panic!("expected UTF-8 error, got {:?}", other),
+    }
+    match &results[2] {
+        Err(e @ JsonlError::Parse { .. }) => {
+            assert_eq!(e.line(), Some(3));
+            assert_eq!(e.offset(), None);
+        }
+        other => panic!("expected parse error, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_stream_errors_are_forwarded() {
+    let items = vec![
+        Ok("{\"value\": 1}".to_string()),
+        Err(std::io::Error::other("connection reset")),
+    ];
+    let results: Vec<_> = stream::iter(items)
+        .jsonl()
+        .deserialize_values()
+        .collect()
+        .await;
+
+    assert!(results[0].is_ok());
+    assert!(matches!(results[1], Err(JsonlError::Io(_))));
+}
+
+#[tokio::test]
+async fn test_builder_settings_apply_to_streams() {
+    let builder = JsonlBuilder::new().comment_prefix("#");
+    let mut records = builder
+        .lines(lines(&[
+            "# header",
+            "{\"value\": 1}",
+            "oops",
+            "{\"value\": 3}",
+        ]))
+        .deserialize_with::<SimpleRecord>(ErrorPolicy::Skip);
+
+    let mut values = Vec::new();
+    while let Some(record) = records.next().await {
+        values.push(record.unwrap().value);
+    }
+    assert_eq!(values, vec![1, 3]);
+    assert_eq!(records.skipped(), 1);
+}
+
+#[tokio::test]
+async fn test_buf_read_adapter() {
+    let data = "{\"value\": 1}\n{\"value\": 2}\n";
+    let values: Vec<_> = BufReader::new(Cursor::new(data.as_bytes()))
+        .jsonl()
+        .deserialize::<SimpleRecord>()
+        .map(|record| record.unwrap().value)
+        .collect()
+        .await;
+
+    assert_eq!(values, vec![1, 2]);
+}
+
+/// Hands out a few bytes per fill and refuses plain reads, so only its own
+/// buffer can be used.
+struct Chunked {
+    data: &'static [u8],
+    chunk: usize,
+}
+
+impl AsyncRead for Chunked {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        _: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        panic!("read around the buffer");
+    }
+}
+
+impl AsyncBufRead for Chunked {
+    fn poll_fill_buf(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<std::io::Result<&[u8]>> {
+        let this = self.get_mut();
+        let len = this.chunk.min(this.data.len());
+        Poll::Ready(Ok(&this.data[..len]))
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        let this = self.get_mut();
+        this.data = &this.data[amt..];
+    }
+}
+
+#[tokio::test]
+async fn test_buf_read_adapter_reads_from_its_buffer() {
+    let reader = Chunked {
+        data: b"{\"value\": 1}\n\n{\"value\": 2}",
+        chunk: 5,
+    };
+    let values: Vec<_> = reader
+        .jsonl()
+        .deserialize::<SimpleRecord>()
+        .map(|record| record.unwrap().value)
+        .collect()
+        .await;
+
+    assert_eq!(values, vec![1, 2]);
+}
@@ -0,0 +1,221 @@
+use async_jsonl::{Jsonl, JsonlDeserialize, JsonlError};
+use futures::StreamExt;
+use serde::Deserialize;
+use std::io::Cursor;
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct User {
+    name: String,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Event {
+    target: String,
+}
+
+#[derive(Debug, PartialEq)]
+enum Record {
+    User(User),
+    Event(Event),
+    Other(String),
+}
+
+impl From<User> for Record {
+    fn from(user: User) -> Self {
+        Record::User(user)
+    }
+}
+
+impl From<Event> for Record {
+    fn from(event: Event) -> Self {
+        Record::Event(event)
+    }
+}
+
+const MIXED: &str = r#"{"type": "user", "name": "Alice"}
+{"target": "button", "meta": {"type": "user"}, "type": "event"}
+{"type": "product", "price": 3}
+{"name": "no tag"}
+"#;
+
+fn reader(data: &str) -> Jsonl<Cursor<Vec<u8>>> {
+    Jsonl::new(Cursor::new(data.as_bytes().to_vec()))
+}
+
+#[tokio::test]
+async fn test_tagged_dispatch_into_enum_with_fallback() {
+    let records: Vec<Record> = reader(MIXED)
+        .deserialize_tagged::<Record>("type")
+        .on::<User>("user")
+        .on::<Event>("event")
+        .fallback(|record| Ok(Record::Other(record.get().to_string())))
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect::<Result<_, _>>()
+        .unwrap();
+
+    assert_eq!(
+        records,
+        vec![
+            Record::User(User {
+                name: "Alice".to_string()
+            }),
+            Record::Event(Event {
+                target: "button".to_string()
+            }),
+            Record::Other(r#"{"type": "product", "price": 3}"#.to_string()),
+            Record::Other(r#"{"name": "no tag"}"#.to_string()),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_tagged_unknown_and_missing_tags_without_fallback() {
+    let results: Vec<_> = reader(MIXED)
+        .deserialize_tagged::<Record>("type")
+        .on::<User>("user")
+        .on::<Event>("event")
+        .collect()
+        .await;
+
+    assert_eq!(results.len(), 4);
+    assert!(results[0].is_ok() && results[1].is_ok());
+    match &results[2] {
+        Err(JsonlError::UnknownTag { location, tag }) => {
+            assert_eq!(location.line, Some(3));
+            assert_eq!(tag.as_deref(), Some("product"));
+        }
+        other => panic!("expected an unknown tag, got {:?}", other),
+    }
+    assert!(matches!(
+        &results[3],
+        Err(JsonlError::UnknownTag { tag: None, .. })
+    ));
+}
+
+#[tokio::test]
+async fn test_tagged_reports_records_that_do_not_fit() {
+    let data = "{\"type\": \"user\", \"name\": 5}\n[1, 2]\n{\"type\": 7}\n{\"type\": \"us\\u0065r\", \"name\": \"Bob\"}\n";
+    let results: Vec<_> = reader(data)
+        .deserialize_tagged::<Record>("type")
+        .on::<User>("user")
+        .collect()
+        .await;
+
+    assert_eq!(results.len(), 4);
+    // A registered type that doesn't fit
+    let error = results[0].as_ref().unwrap_err();
+    assert!(matches!(error, JsonlError::Parse { .. }), "{:?}", error);
+    assert_eq!(error.line(), Some(1));
+    // A tag that isn't a string
+    match &results[2] {
+        Err(JsonlError::InvalidTag { location, value }) => {
+            assert_eq!(location.line, Some(3));
+            assert_eq!(value, "7");
+        }
+        other => panic!("expected an invalid tag, got {:?}", other),
+    }
+    // Anything but an object has no tag
+    assert!(matches!(
+        &results[1],
+        Err(JsonlError::UnknownTag { tag: None, .. })
+    ));
+    // Escaped tags are compared once unescaped
+    assert_eq!(
+        results[3].as_ref().unwrap(),
+        &Record::User(User {
+            name: "Bob".to_string()
+        })
+    );
+}
+
+#[tokio::test]
+async fn test_tagged_non_string_tags() {
+    let data = "{\"type\": {\"name\": \"user\"}}\n{\"type\": null}\n";
+    let results: Vec<_> = reader(data)
+        .deserialize_tagged::<Record>("type")
+        .on::<User>("user")
+        .collect()
+        .await;
+    match &results[0] {
+        Err(JsonlError::InvalidTag { value, .. }) => assert_eq!(value, r#"{"name": "user"}"#),
+        other => panic!("expected an invalid tag, got {:?}", other),
+    }
+    assert!(matches!(
+        &results[1],
+        Err(JsonlError::InvalidTag { value, .. }) if value == "null"
+    ));
+
+    // The fallback gets them like any unregistered tag
+    let results: Vec<_> = reader(data)
+        .deserialize_tagged::<Record>("type")
+        .on::<User>("user")
+        .fallback(|record| Ok(Record::Other(record.get().to_string())))
+        .map(Result::unwrap)
+        .collect()
+        .await;
+    assert_eq!(
+        results,
+        vec![
+            Record::Other(r#"{"type": {"name": "user"}}"#.to_string()),
+            Record::Other(r#"{"type": null}"#.to_string()),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_tagged_async_handlers_run_in_order() {
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let users = seen.clone();
+    let events = seen.clone();
+
+    let results: Vec<_> = reader(MIXED)
+        .deserialize_tagged::<()>("type")
+        .handle("user", move |user: User| {
+            let seen = users.clone();
+            async move {
+                tokio::task::yield_now().await;
+                seen.lock().unwrap().push(format!("user {}", user.name));
+                Ok(())
+            }
+        })
+        .handle("event", move |event: Event| {
+            let seen = events.clone();
+            async move {
+                seen.lock().unwrap().push(format!("event {}", event.target));
+                Ok(())
+            }
+        })
+        .fallback(|_| Ok(()))
+        .collect()
+        .await;
+
+    assert_eq!(results.len(), 4);
+    assert!(results.iter().all(Result::is_ok));
+    assert_eq!(
+        *seen.lock().unwrap(),
+        vec!["user Alice".to_string(), "event button".to_string()]
+    );
+}
+
+#[tokio::test]
+async fn test_tagged_handler_errors_are_yielded() {
+    let results: Vec<_> = reader(MIXED)
+        .deserialize_tagged::<String>("type")
+        .handle("user", |_: User| async { Err(JsonlError::MissingHeader) })
+        .on::<Event>("event")
+        .fallback(|record| Ok(record.get().to_string()))
+        .collect()
+        .await;
+
+    assert!(matches!(results[0], Err(JsonlError::MissingHeader)));
+    assert!(results[1..].iter().all(Result::is_ok));
+}
+
+impl From<Event> for String {
+    fn from(event: Event) -> Self {
+        event.target
+    }
+}
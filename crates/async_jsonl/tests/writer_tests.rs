@@ -0,0 +1,142 @@
+use async_jsonl::{Framing, Jsonl, JsonlDeserialize, JsonlError, JsonlWriter};
+use futures::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::value::RawValue;
+use std::io::{self, Cursor};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::io::AsyncWrite;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Note {
+    id: u32,
+    text: String,
+}
+
+fn note(id: u32, text: &str) -> Note {
+    Note {
+        id,
+        text: text.to_string(),
+    }
+}
+
+/// Takes one byte per write, and is not ready every other time
+#[derive(Default)]
+struct Trickle {
+    data: Vec<u8>,
+    stalled: bool,
+    shut_down: bool,
+}
+
+impl AsyncWrite for Trickle {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.stalled = !self.stalled;
+        if self.stalled {
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+        self.data.push(buf[0]);
+        Poll::Ready(Ok(1))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.shut_down = true;
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[tokio::test]
+async fn test_deserialize_stream_forwards_into_writer() {
+    let data = "{\"id\": 1, \"text\": \"a\"}\n\n{\"id\": 2, \"text\": \"b\"}\n";
+    let mut writer = JsonlWriter::new(Vec::new());
+    Jsonl::new(Cursor::new(data.as_bytes()))
+        .deserialize::<Note>()
+        .forward(&mut writer)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        writer.into_inner(),
+        b"{\"id\":1,\"text\":\"a\"}\n{\"id\":2,\"text\":\"b\"}\n"
+    );
+}
+
+#[tokio::test]
+async fn test_records_never_span_lines() {
+    let pretty =
+        RawValue::from_string("{\n  \"id\": 3,\r\n  \"text\": \"c\"\n}".to_string()).unwrap();
+    let mut writer = JsonlWriter::new(Vec::new());
+    writer.write(&note(1, "two\nlines\r\n")).await.unwrap();
+    writer.write(&pretty).await.unwrap();
+    let output = writer.into_inner();
+
+    assert_eq!(output.iter().filter(|&&b| b == b'\n').count(), 2);
+    let notes: Vec<Note> = Jsonl::new(Cursor::new(output))
+        .deserialize::<Note>()
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(notes, vec![note(1, "two\nlines\r\n"), note(3, "c")]);
+}
+
+#[tokio::test]
+async fn test_write_all_stops_at_the_first_error() {
+    let records = futures::stream::iter(vec![
+        Ok(note(1, "a")),
+        Ok(note(2, "b")),
+        Err(JsonlError::MissingHeader),
+        Ok(note(3, "c")),
+    ]);
+    let mut writer = JsonlWriter::new(Vec::new());
+    let error = writer.write_all(records).await.unwrap_err();
+    assert!(matches!(error, JsonlError::MissingHeader));
+    assert_eq!(
+        String::from_utf8(writer.into_inner())
+            .unwrap()
+            .lines()
+            .count(),
+        2
+    );
+
+    let records = futures::stream::iter((1..=3).map(|id| Ok(note(id, "x"))));
+    let mut writer = JsonlWriter::new(Vec::new());
+    assert_eq!(writer.write_all(records).await.unwrap(), 3);
+}
+
+#[tokio::test]
+async fn test_partial_writes_keep_records_whole() {
+    let mut writer = JsonlWriter::new(Trickle::default()).framing(Framing::JsonSeq);
+    writer.send(note(1, "a")).await.unwrap();
+    writer.write(&note(2, "b")).await.unwrap();
+    writer.feed(note(3, "c")).await.unwrap();
+    let trickle = writer.finish().await.unwrap();
+
+    assert!(trickle.shut_down);
+    assert_eq!(
+        trickle.data,
+        b"\x1e{\"id\":1,\"text\":\"a\"}\n\x1e{\"id\":2,\"text\":\"b\"}\n\x1e{\"id\":3,\"text\":\"c\"}\n"
+    );
+}
+
+#[tokio::test]
+async fn test_sink_reports_serialization_errors() {
+    let mut writer = JsonlWriter::new(Vec::new());
+    writer.feed(note(1, "a")).await.unwrap();
+    let bad: std::collections::HashMap<(u8, u8), u8> = [((1, 2), 3)].into();
+    let error = writer.send(bad).await.unwrap_err();
+    assert!(matches!(error, JsonlError::Serialize(_)));
+
+    // The record fed before the failed one is still written
+    writer.flush().await.unwrap();
+    assert_eq!(writer.into_inner(), b"{\"id\":1,\"text\":\"a\"}\n");
+}
@@ -116,5 +116,42 @@ fn bench_async_vs_sync_comparison(c: &mut Criterion) {
     group.finish();
 }
 
-criterion_group!(benches, bench_async_vs_sync_comparison);
+fn bench_raw_long_lines(c: &mut Criterion) {
+    let mut group = c.benchmark_group("raw_long_lines");
+    let rt = Runtime::new().unwrap();
+
+    // Time per byte should stay flat as lines grow
+    for &line_len in &[1 << 20, 4 << 20, 16 << 20] {
+        let mut test_data: Vec<u8> = (0..line_len).map(|i| b'a' + (i % 26) as u8).collect();
+        test_data.push(b'\n');
+        group.throughput(Throughput::Bytes(line_len as u64));
+
+        group.bench_with_input(
+            BenchmarkId::new("read_line_raw", line_len),
+            &test_data,
+            |b, data| {
+                b.iter(|| {
+                    rt.block_on(async {
+                        let mut reader = RevBufReader::new(Cursor::new(data.as_slice()));
+                        let mut line = Vec::new();
+                        while let Some(range) =
+                            reader.read_line_raw(&mut line, usize::MAX).await.unwrap()
+                        {
+                            black_box(range);
+                        }
+                        line.len()
+                    })
+                })
+            },
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_async_vs_sync_comparison,
+    bench_raw_long_lines
+);
 criterion_main!(benches);
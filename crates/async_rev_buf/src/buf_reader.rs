@@ -1,9 +1,11 @@
 use crate::DEFAULT_BUF_SIZE;
 use pin_project_lite::pin_project;
-use std::io::{Result as IoResult, SeekFrom};
+use std::future::poll_fn;
+use std::io::{Error as IoError, ErrorKind, Result as IoResult, SeekFrom};
+use std::ops::Range;
 use std::pin::Pin;
-use std::task::{Context, Poll};
-use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt, ReadBuf};
+use std::task::{ready, Context, Poll};
+use tokio::io::{AsyncRead, AsyncSeek, ReadBuf};
 
 pin_project! {
     /// A high-performance buffered reader that reads lines in reverse order.
@@ -17,9 +19,84 @@ pin_project! {
         file_pos: u64,     // Current position in file
         file_size: u64,    // Total file size (cached)
         initialized: bool, // Whether we've initialized file size
+        pending: Pending,  // Seek or read in progress
+        line_end: Option<u64>, // End of the raw line being read, if one was started
+        tail_checked: bool, // Whether a trailing newline was looked for
+        exhausted: bool,   // Whether the raw line at the start of the file was returned
+        newline: Newline,  // What ends a raw line
     }
 }
 
+/// How raw lines end, for [`RevBufReader::poll_read_line_raw`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Newline {
+    /// A `\n` byte, as in UTF-8 and ASCII text.
+    #[default]
+    Byte,
+    /// A little-endian UTF-16 `\n` code unit: `0A 00` at an even offset.
+    Utf16Le,
+    /// A big-endian UTF-16 `\n` code unit: `00 0A` at an even offset.
+    Utf16Be,
+    /// Any other single byte, such as the ASCII record separator `0x1E`
+    /// that starts each text of an RFC 7464 JSON text sequence.
+    Custom(u8),
+}
+
+impl Newline {
+    /// Length of the newline in bytes.
+    fn len(self) -> usize {
+        match self {
+            Newline::Byte | Newline::Custom(_) => 1,
+            Newline::Utf16Le | Newline::Utf16Be => 2,
+        }
+    }
+
+    /// The newline as a single byte.
+    fn byte(self) -> Option<u8> {
+        match self {
+            Newline::Byte => Some(b'\n'),
+            Newline::Custom(byte) => Some(byte),
+            Newline::Utf16Le | Newline::Utf16Be => None,
+        }
+    }
+
+    /// The newline as a UTF-16 code unit.
+    fn unit(self) -> Option<[u8; 2]> {
+        match self {
+            Newline::Byte | Newline::Custom(_) => None,
+            Newline::Utf16Le => Some([0x0A, 0]),
+            Newline::Utf16Be => Some([0, 0x0A]),
+        }
+    }
+
+    /// Start of the last newline in `data`, whose first byte is at an even offset.
+    fn rfind(self, data: &[u8]) -> Option<usize> {
+        match self.unit() {
+            None => data.iter().rposition(|&b| Some(b) == self.byte()),
+            Some(unit) => data
+                .chunks_exact(2)
+                .rposition(|chunk| chunk == unit)
+                .map(|index| index * 2),
+        }
+    }
+
+    /// Whether `data`, whose first byte is at an even offset, ends with a newline.
+    fn ends(self, data: &[u8]) -> bool {
+        match self.unit() {
+            None => data.last().copied() == self.byte(),
+            Some(unit) => data.chunks_exact(2).remainder().is_empty() && data.ends_with(&unit),
+        }
+    }
+}
+
+/// IO operation left unfinished by a pending poll.
+#[derive(Debug, Clone, Copy)]
+enum Pending {
+    Idle,
+    Seek,
+    Read { length: usize, filled: usize },
+}
+
 impl<R: AsyncRead> RevBufReader<R> {
     /// Creates a new reverse buffered reader with default capacity.
     pub fn new(inner: R) -> Self {
@@ -36,6 +113,11 @@ impl<R: AsyncRead> RevBufReader<R> {
             file_pos: 0,
             file_size: 0,
             initialized: false,
+            pending: Pending::Idle,
+            line_end: None,
+            tail_checked: false,
+            exhausted: false,
+            newline: Newline::Byte,
         }
     }
 
@@ -59,6 +141,18 @@ impl<R: AsyncRead> RevBufReader<R> {
         self.inner
     }
 
+    /// Sets what ends the lines returned by
+    /// [`poll_read_line_raw`](Self::poll_read_line_raw), `\n` bytes by default.
+    ///
+    /// Must be set before the first raw line is read. With UTF-16 newlines,
+    /// chunks are read at even offsets so no code unit is ever split.
+    pub fn set_newline(&mut self, newline: Newline) {
+        self.newline = newline;
+        if self.buf.len() < newline.len() {
+            self.buf = vec![0; newline.len()].into_boxed_slice();
+        }
+    }
+
     /// Returns a reference to the internally buffered data.
     pub fn buffer(&self) -> &[u8] {
         &self.buf[self.pos..self.cap]
@@ -66,55 +160,72 @@ impl<R: AsyncRead> RevBufReader<R> {
 }
 
 impl<R: AsyncRead + AsyncSeek + Unpin> RevBufReader<R> {
-    /// Initialize file size if not already done - optimized to do this once
-    async fn ensure_initialized(&mut self) -> IoResult<()> {
-        if !self.initialized {
-            self.file_size = self.inner.seek(SeekFrom::End(0)).await?;
-            self.file_pos = self.file_size;
-            self.initialized = true;
-        }
-        Ok(())
-    }
-
-    /// Efficiently seek backward in the file
-    async fn seek_back(&mut self, length: usize) -> IoResult<usize> {
-        if self.file_pos == 0 {
-            return Ok(0);
+    /// Make buffered data available, reading the previous chunk of the file
+    /// once the buffer is used up. The buffer stays empty at the start of the file.
+    fn poll_fill(&mut self, cx: &mut Context<'_>) -> Poll<IoResult<()>> {
+        loop {
+            match self.pending {
+                Pending::Idle => {
+                    if self.initialized && (self.pos > 0 || self.file_pos == 0) {
+                        return Poll::Ready(Ok(()));
+                    }
+                    let target = if self.initialized {
+                        let mut length = std::cmp::min(self.buf.len() as u64, self.file_pos);
+                        if self.newline.unit().is_some() {
+                            // Keep code units whole
+                            length -= (self.file_pos - length) % 2;
+                        }
+                        SeekFrom::Start(self.file_pos - length)
+                    } else {
+                        SeekFrom::End(0)
+                    };
+                    Pin::new(&mut self.inner).start_seek(target)?;
+                    self.pending = Pending::Seek;
+                }
+                Pending::Seek => {
+                    let result = ready!(Pin::new(&mut self.inner).poll_complete(cx));
+                    self.pending = Pending::Idle;
+                    let position = result?;
+                    if !self.initialized {
+                        self.file_size = position;
+                        self.file_pos = position;
+                        self.initialized = true;
+                        continue;
+                    }
+                    let length = (self.file_pos - position) as usize;
+                    self.file_pos = position;
+                    self.cap = 0;
+                    self.pos = 0;
+                    self.pending = Pending::Read { length, filled: 0 };
+                }
+                Pending::Read { length, filled } => {
+                    if filled == length {
+                        self.cap = length;
+                        self.pos = length; // Start from the end of the buffer
+                        self.pending = Pending::Idle;
+                        continue;
+                    }
+                    let mut read_buf = ReadBuf::new(&mut self.buf[filled..length]);
+                    ready!(Pin::new(&mut self.inner).poll_read(cx, &mut read_buf))?;
+                    let read = read_buf.filled().len();
+                    if read == 0 {
+                        return Poll::Ready(Err(IoError::new(
+                            ErrorKind::UnexpectedEof,
+                            "source shrank while being read",
+                        )));
+                    }
+                    self.pending = Pending::Read {
+                        length,
+                        filled: filled + read,
+                    };
+                }
+            }
         }
-
-        // Calculate how much we can actually seek back
-        let seek_amount = std::cmp::min(length as u64, self.file_pos) as usize;
-        let new_pos = self.file_pos - seek_amount as u64;
-
-        // Seek to the new position
-        self.inner.seek(SeekFrom::Start(new_pos)).await?;
-        self.file_pos = new_pos;
-        self.cap = 0;
-        self.pos = 0;
-
-        Ok(seek_amount)
     }
 
     /// Fill the buffer with data from the current position
     async fn fill_buffer(&mut self) -> IoResult<&[u8]> {
-        if self.pos == 0 {
-            let length = self.seek_back(self.buf.len()).await?;
-            if length == 0 {
-                return Ok(&[]);
-            }
-
-            // Read the data from current position
-            let mut total_read = 0;
-            while total_read < length {
-                match self.inner.read(&mut self.buf[total_read..length]).await? {
-                    0 => break,
-                    n => total_read += n,
-                }
-            }
-
-            self.cap = total_read;
-            self.pos = total_read; // Start from the end of the buffer
-        }
+        poll_fn(|cx| self.poll_fill(cx)).await?;
         Ok(&self.buf[0..self.pos])
     }
 
@@ -125,12 +236,6 @@ impl<R: AsyncRead + AsyncSeek + Unpin> RevBufReader<R> {
 
     /// Core optimized line reading implementation
     async fn read_line_internal(&mut self, buf: &mut String) -> IoResult<usize> {
-        self.ensure_initialized().await?;
-
-        if self.file_size == 0 {
-            return Ok(0);
-        }
-
         let mut line_buffer = Vec::new();
 
         loop {
@@ -200,6 +305,116 @@ impl<R: AsyncRead + AsyncSeek + Unpin> RevBufReader<R> {
         }
     }
 
+    /// Polls for the previous line as raw bytes.
+    ///
+    /// Lines end with a `\n` byte only, not `\r`, unless
+    /// [`set_newline`](Self::set_newline) says otherwise. The line is written
+    /// to `buf` without its newline, and the returned range is where the whole
+    /// line lies in the source. Nothing is decoded or trimmed, and empty
+    /// lines are returned like any other, except that a newline ending the
+    /// source does not start an empty last line. Only the first `limit` bytes
+    /// of the line are kept, so memory stays within twice `limit`; a range
+    /// longer than `buf` tells that the line was cut short. Reading a line
+    /// takes time linear in its length.
+    ///
+    /// While this returns `Poll::Pending`, the part of the line read so far is
+    /// kept in `buf`, back to front, so the next poll must pass the same
+    /// buffer. Raw lines
+    /// and [`next_line`](Self::next_line) share the read position and should
+    /// not be mixed on one reader.
+    pub fn poll_read_line_raw(
+        &mut self,
+        cx: &mut Context<'_>,
+        buf: &mut Vec<u8>,
+        limit: usize,
+    ) -> Poll<IoResult<Option<Range<u64>>>> {
+        loop {
+            ready!(self.poll_fill(cx))?;
+            if !self.tail_checked {
+                self.tail_checked = true;
+                self.exhausted = self.file_size == 0;
+                if self.newline.ends(&self.buf[..self.pos]) {
+                    self.consume(self.newline.len());
+                    continue;
+                }
+            }
+
+            let end = match self.line_end {
+                Some(end) => end,
+                None if self.exhausted => return Poll::Ready(Ok(None)),
+                None => {
+                    buf.clear();
+                    let end = self.file_pos + self.pos as u64;
+                    self.line_end = Some(end);
+                    end
+                }
+            };
+
+            let data = &self.buf[..self.pos];
+            let newline = self.newline.rfind(data);
+            let start = newline.map_or(0, |newline| newline + self.newline.len());
+            // Reading backwards, so the line is gathered back to front and
+            // turned around once complete, rather than prepending every chunk
+            let chunk = &data[start..];
+            let kept = std::cmp::min(chunk.len(), limit);
+            buf.extend(chunk[..kept].iter().rev());
+            if buf.len() >= limit.saturating_mul(2) {
+                // Drop the line's later bytes in batches, so each is moved once
+                buf.drain(..buf.len() - limit);
+            }
+
+            match newline {
+                Some(newline) => {
+                    self.pos = newline; // Consume the line and its newline
+                    self.line_end = None;
+                    finish_raw_line(buf, limit);
+                    return Poll::Ready(Ok(Some(self.file_pos + start as u64..end)));
+                }
+                None if self.file_pos == 0 => {
+                    self.pos = 0;
+                    self.line_end = None;
+                    self.exhausted = true;
+                    finish_raw_line(buf, limit);
+                    return Poll::Ready(Ok(Some(0..end)));
+                }
+                None => self.pos = 0,
+            }
+        }
+    }
+
+    /// Reads the previous line as raw bytes into `buf`.
+    ///
+    /// See [`poll_read_line_raw`](Self::poll_read_line_raw) for how lines are
+    /// split and bounded. Returns `None` once the start of the source is reached.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use async_rev_buf::RevBufReader;
+    /// use std::io::Cursor;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut reader = RevBufReader::new(Cursor::new("first\r\n\nthird\n"));
+    ///     let mut line = Vec::new();
+    ///
+    ///     assert_eq!(reader.read_line_raw(&mut line, usize::MAX).await?, Some(8..13));
+    ///     assert_eq!(line, b"third");
+    ///     assert_eq!(reader.read_line_raw(&mut line, usize::MAX).await?, Some(7..7));
+    ///     assert_eq!(reader.read_line_raw(&mut line, 3).await?, Some(0..6));
+    ///     assert_eq!(line, b"fir");
+    ///     assert_eq!(reader.read_line_raw(&mut line, usize::MAX).await?, None);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn read_line_raw(
+        &mut self,
+        buf: &mut Vec<u8>,
+        limit: usize,
+    ) -> IoResult<Option<Range<u64>>> {
+        poll_fn(|cx| self.poll_read_line_raw(cx, buf, limit)).await
+    }
+
     /// Returns a stream of lines read in reverse order
     pub fn lines(self) -> crate::Lines<R>
     where
@@ -209,6 +424,15 @@ impl<R: AsyncRead + AsyncSeek + Unpin> RevBufReader<R> {
     }
 }
 
+/// Turns a raw line gathered back to front the right way round, keeping its
+/// first `limit` bytes.
+fn finish_raw_line(buf: &mut Vec<u8>, limit: usize) {
+    if buf.len() > limit {
+        buf.drain(..buf.len() - limit);
+    }
+    buf.reverse();
+}
+
 // AsyncRead implementation for completeness
 impl<R: AsyncRead + Unpin> AsyncRead for RevBufReader<R> {
     fn poll_read(
@@ -71,7 +71,7 @@
 mod buf_reader;
 mod lines;
 
-pub use buf_reader::RevBufReader;
+pub use buf_reader::{Newline, RevBufReader};
 pub use lines::Lines;
 
 /// Default buffer size: 8 KB
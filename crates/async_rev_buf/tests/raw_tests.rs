@@ -0,0 +1,207 @@
+//! Tests for reading raw lines with their byte ranges
+
+use async_rev_buf::{Newline, RevBufReader};
+use std::io::{Cursor, SeekFrom};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::task::{ready, Context, Poll};
+use tokio::io::{AsyncRead, AsyncSeek, ReadBuf};
+
+async fn raw_lines(data: &[u8], capacity: usize, limit: usize) -> Vec<(Vec<u8>, u64, u64)> {
+    let mut reader = RevBufReader::with_capacity(capacity, Cursor::new(data.to_vec()));
+    let mut line = Vec::new();
+    let mut result = Vec::new();
+    while let Some(range) = reader.read_line_raw(&mut line, limit).await.unwrap() {
+        result.push((line.clone(), range.start, range.end));
+    }
+    result
+}
+
+#[tokio::test]
+async fn test_raw_lines_keep_blank_lines_and_carriage_returns() {
+    let result = raw_lines(b"a\r\n\nbc\n", 8, usize::MAX).await;
+    assert_eq!(
+        result,
+        vec![
+            (b"bc".to_vec(), 4, 6),
+            (b"".to_vec(), 3, 3),
+            (b"a\r".to_vec(), 0, 2),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_raw_lines_without_trailing_newline() {
+    let result = raw_lines(b"one\ntwo", 8, usize::MAX).await;
+    assert_eq!(
+        result,
+        vec![(b"two".to_vec(), 4, 7), (b"one".to_vec(), 0, 3)]
+    );
+}
+
+#[tokio::test]
+async fn test_raw_lines_empty_and_newline_only() {
+    assert!(raw_lines(b"", 8, usize::MAX).await.is_empty());
+    assert_eq!(
+        raw_lines(b"\n", 8, usize::MAX).await,
+        vec![(b"".to_vec(), 0, 0)]
+    );
+    assert_eq!(
+        raw_lines(b"\n\n", 8, usize::MAX).await,
+        vec![(b"".to_vec(), 1, 1), (b"".to_vec(), 0, 0)]
+    );
+}
+
+#[tokio::test]
+async fn test_raw_lines_across_small_buffers() {
+    let data: String = (0..100).map(|i| format!("line {}\n", i)).collect();
+    let result = raw_lines(data.as_bytes(), 3, usize::MAX).await;
+
+    assert_eq!(result.len(), 100);
+    for (i, (line, start, end)) in result.iter().enumerate() {
+        let expected = format!("line {}", 99 - i);
+        assert_eq!(line, expected.as_bytes());
+        assert_eq!(&data[*start as usize..*end as usize], expected);
+    }
+}
+
+#[tokio::test]
+async fn test_raw_lines_keep_the_start_of_long_lines() {
+    let long = "x".repeat(50) + "y";
+    let data = format!("{}\nshort\n{}", long, long);
+    let result = raw_lines(data.as_bytes(), 4, 10).await;
+
+    assert_eq!(result.len(), 3);
+    assert_eq!(result[0], (b"xxxxxxxxxx".to_vec(), 58, 109));
+    assert_eq!(result[1], (b"short".to_vec(), 52, 57));
+    assert_eq!(result[2], (b"xxxxxxxxxx".to_vec(), 0, 51));
+}
+
+fn utf16(text: &str, big_endian: bool) -> Vec<u8> {
+    text.encode_utf16()
+        .flat_map(|unit| {
+            if big_endian {
+                unit.to_be_bytes()
+            } else {
+                unit.to_le_bytes()
+            }
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_raw_lines_utf16() {
+    // U+010A and U+0A00 contain a 0x0A byte without being newlines
+    let text = "a\u{10A}\n\u{A00}b\n\nc\n";
+    for (big_endian, newline) in [(false, Newline::Utf16Le), (true, Newline::Utf16Be)] {
+        let data = utf16(text, big_endian);
+        for capacity in [2, 3, 5, 64] {
+            let mut reader = RevBufReader::with_capacity(capacity, Cursor::new(data.clone()));
+            reader.set_newline(newline);
+            let mut line = Vec::new();
+            let mut result = Vec::new();
+            while let Some(range) = reader.read_line_raw(&mut line, usize::MAX).await.unwrap() {
+                result.push((line.clone(), range.start, range.end));
+            }
+
+            assert_eq!(
+                result,
+                vec![
+                    (utf16("c", big_endian), 14, 16),
+                    (vec![], 12, 12),
+                    (utf16("\u{A00}b", big_endian), 6, 10),
+                    (utf16("a\u{10A}", big_endian), 0, 4),
+                ],
+                "capacity {}, big endian {}",
+                capacity,
+                big_endian
+            );
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_raw_lines_split_at_custom_byte() {
+    let data = b"\x1e{\"a\":1}\n\x1e2\n";
+    let mut reader = RevBufReader::with_capacity(4, Cursor::new(data.to_vec()));
+    reader.set_newline(Newline::Custom(0x1E));
+
+    let mut line = Vec::new();
+    let mut result = Vec::new();
+    while let Some(range) = reader.read_line_raw(&mut line, usize::MAX).await.unwrap() {
+        result.push((line.clone(), range.start, range.end));
+    }
+    assert_eq!(
+        result,
+        vec![
+            (b"2\n".to_vec(), 10, 12),
+            (b"{\"a\":1}\n".to_vec(), 1, 9),
+            (b"".to_vec(), 0, 0),
+        ]
+    );
+}
+
+/// Source that counts the bytes read from it.
+struct Counting {
+    inner: Cursor<Vec<u8>>,
+    read: Arc<AtomicUsize>,
+}
+
+impl AsyncRead for Counting {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        let filled = buf.filled().len();
+        ready!(Pin::new(&mut self.inner).poll_read(cx, buf))?;
+        self.read
+            .fetch_add(buf.filled().len() - filled, Ordering::Relaxed);
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl AsyncSeek for Counting {
+    fn start_seek(mut self: Pin<&mut Self>, position: SeekFrom) -> std::io::Result<()> {
+        Pin::new(&mut self.inner).start_seek(position)
+    }
+
+    fn poll_complete(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<u64>> {
+        Pin::new(&mut self.inner).poll_complete(cx)
+    }
+}
+
+#[tokio::test]
+async fn test_raw_lines_long_lines_read_each_byte_once() {
+    // Timing of long lines is covered by the `raw_long_lines` benchmark
+    let long: Vec<u8> = (0..4 * 1024 * 1024)
+        .map(|i| b'a' + (i % 26) as u8)
+        .collect();
+    let mut data = b"first\n".to_vec();
+    data.extend_from_slice(&long);
+    data.push(b'\n');
+
+    let read = Arc::new(AtomicUsize::new(0));
+    let source = Counting {
+        inner: Cursor::new(data.clone()),
+        read: read.clone(),
+    };
+    let mut reader = RevBufReader::with_capacity(1024, source);
+    let mut line = Vec::new();
+    let range = reader.read_line_raw(&mut line, usize::MAX).await.unwrap();
+    assert!(line == long);
+    assert_eq!(range, Some(6..6 + long.len() as u64));
+    let range = reader.read_line_raw(&mut line, usize::MAX).await.unwrap();
+    assert_eq!((&line[..], range), (&b"first"[..], Some(0..5)));
+    assert_eq!(
+        reader.read_line_raw(&mut line, usize::MAX).await.unwrap(),
+        None
+    );
+    assert_eq!(read.load(Ordering::Relaxed), data.len());
+
+    // The first bytes are kept however the limit falls across chunks
+    let result = raw_lines(&data, 1000, 2500).await;
+    assert!(result[0].0 == long[..2500]);
+    assert_eq!(result[1].0, b"first");
+}